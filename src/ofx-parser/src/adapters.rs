@@ -1,2 +1,10 @@
+pub mod annotation_file;
+pub mod budget_file;
+pub mod filter_expression;
 mod models;
 pub mod ofx_parser;
+pub mod report_export;
+pub mod rules_file;
+pub mod split_expression;
+pub mod sqlite_store;
+pub mod timezone;
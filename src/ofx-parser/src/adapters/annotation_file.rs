@@ -0,0 +1,192 @@
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use log::debug;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::domain::entities::{
+    annotation::{Annotation, Annotations, Split},
+    ofx::{AccountKey, TransactionRef},
+};
+
+#[derive(Error, Debug)]
+pub enum AnnotationFileError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid annotation file: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+pub type AnnotationFileResult<T> = Result<T, AnnotationFileError>;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AnnotationFileJson {
+    annotations: Vec<AnnotationJson>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AnnotationJson {
+    bankid: String,
+    acctid: String,
+    accttype: String,
+    fitid: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    splits: Vec<SplitJson>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SplitJson {
+    category: String,
+    amount: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    memo: Option<String>,
+}
+
+/// Sidecar file kept next to the statements, named after them with `.annotations.json` added.
+pub fn sidecar_path(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".annotations.json");
+    name.into()
+}
+
+/// Reads annotations back. A missing file is the same as an empty one.
+pub fn load_annotations(path: &Path) -> AnnotationFileResult<Annotations> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            debug!("No annotations at {}", path.display());
+            return Ok(Annotations::default());
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let file: AnnotationFileJson = serde_json::from_str(&content)?;
+    let mut annotations = Annotations::default();
+    for entry in file.annotations {
+        let (reference, annotation) = entry.into();
+        annotations.insert(reference, annotation);
+    }
+    Ok(annotations)
+}
+
+/// Replaces the file through a temporary one, so a failed write leaves the old one intact.
+pub fn save_annotations(path: &Path, annotations: &Annotations) -> AnnotationFileResult<()> {
+    let file = AnnotationFileJson {
+        annotations: annotations.iter().map(AnnotationJson::from).collect(),
+    };
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+
+    fs::write(&temporary, serde_json::to_string_pretty(&file)?)?;
+    fs::rename(&temporary, path)?;
+    Ok(())
+}
+
+impl From<AnnotationJson> for (TransactionRef, Annotation) {
+    fn from(value: AnnotationJson) -> Self {
+        let reference = TransactionRef {
+            account: AccountKey {
+                bankid: value.bankid,
+                acctid: value.acctid,
+                accttype: value.accttype,
+            },
+            fitid: value.fitid,
+        };
+        let annotation = Annotation {
+            splits: value
+                .splits
+                .into_iter()
+                .map(|s| Split {
+                    category: s.category,
+                    amount: s.amount,
+                    memo: s.memo,
+                })
+                .collect(),
+            notes: value.notes,
+            tags: value.tags,
+        };
+        (reference, annotation)
+    }
+}
+
+impl From<(&TransactionRef, &Annotation)> for AnnotationJson {
+    fn from((reference, annotation): (&TransactionRef, &Annotation)) -> Self {
+        AnnotationJson {
+            bankid: reference.account.bankid.clone(),
+            acctid: reference.account.acctid.clone(),
+            accttype: reference.account.accttype.clone(),
+            fitid: reference.fitid.clone(),
+            notes: annotation.notes.clone(),
+            tags: annotation.tags.clone(),
+            splits: annotation
+                .splits
+                .iter()
+                .map(|s| SplitJson {
+                    category: s.category.clone(),
+                    amount: s.amount,
+                    memo: s.memo.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn round_trips_annotations_and_rejects_unbalanced_splits() {
+        let reference = TransactionRef {
+            account: AccountKey {
+                bankid: "0001".to_string(),
+                acctid: "12345".to_string(),
+                accttype: "CHECKING".to_string(),
+            },
+            fitid: "42".to_string(),
+        };
+        let split = |category: &str, amount| Split {
+            category: category.to_string(),
+            amount,
+            memo: None,
+        };
+        let mut annotations = Annotations::default();
+
+        let unbalanced = Annotation {
+            splits: vec![split("Groceries", -60.0), split("Household", -30.0)],
+            ..Default::default()
+        };
+        assert!(
+            annotations
+                .annotate(reference.clone(), unbalanced, -100.0)
+                .is_err()
+        );
+
+        let annotation = Annotation {
+            splits: vec![split("Groceries", -60.0), split("Household", -40.0)],
+            notes: Some("Weekly shop".to_string()),
+            tags: vec!["family".to_string()],
+        };
+        annotations
+            .annotate(reference.clone(), annotation.clone(), -100.0)
+            .unwrap();
+
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("june.ofx.annotations.json");
+        save_annotations(&path, &annotations).unwrap();
+        let loaded = load_annotations(&path).unwrap();
+
+        assert_eq!(loaded.get(&reference), Some(&annotation));
+        assert_eq!(loaded.len(), 1);
+    }
+}
@@ -0,0 +1,76 @@
+use std::{fs, path::Path};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::domain::services::budget::{Budget, CategoryBudget, Rollover};
+
+#[derive(Error, Debug)]
+pub enum BudgetFileError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid budget file: {0}")]
+    Toml(#[from] toml::de::Error),
+}
+
+pub type BudgetFileResult<T> = Result<T, BudgetFileError>;
+
+/// A TOML budget file looks like:
+///
+/// ```toml
+/// [[budget]]
+/// category = "Groceries"
+/// limit = 800.0
+/// rollover = "unspent"
+/// ```
+///
+/// `rollover` is one of `none` (the default), `unspent` or `full`.
+#[derive(Debug, Deserialize)]
+struct BudgetFileToml {
+    #[serde(default)]
+    budget: Vec<CategoryBudgetToml>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CategoryBudgetToml {
+    category: String,
+    limit: f64,
+    #[serde(default)]
+    rollover: RolloverToml,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum RolloverToml {
+    #[default]
+    None,
+    Unspent,
+    Full,
+}
+
+pub fn load_budget(path: &Path) -> BudgetFileResult<Budget> {
+    parse_budget(&fs::read_to_string(path)?)
+}
+
+pub fn parse_budget(content: &str) -> BudgetFileResult<Budget> {
+    let file: BudgetFileToml = toml::from_str(content)?;
+    Ok(Budget {
+        categories: file.budget.into_iter().map(CategoryBudget::from).collect(),
+    })
+}
+
+impl From<CategoryBudgetToml> for CategoryBudget {
+    fn from(value: CategoryBudgetToml) -> Self {
+        CategoryBudget {
+            category: value.category,
+            limit: value.limit.abs(),
+            rollover: match value.rollover {
+                RolloverToml::None => Rollover::None,
+                RolloverToml::Unspent => Rollover::Unspent,
+                RolloverToml::Full => Rollover::Full,
+            },
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use chrono::NaiveDate;
+use regex::RegexBuilder;
+use thiserror::Error;
+
+use crate::domain::services::{
+    categorization::TextMatch,
+    filter::{Comparison, FilterTerm, TextField, TransactionFilter},
+};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum FilterError {
+    #[error("{0}: unknown field, expected amount, date, type, name, memo or fitid")]
+    UnknownField(String),
+
+    #[error("{0}: invalid amount")]
+    InvalidAmount(String),
+
+    #[error("{0}: invalid date, expected YYYY-MM-DD")]
+    InvalidDate(String),
+
+    #[error("{0}: text can only be compared with : (equals) or ~ (contains)")]
+    InvalidComparison(String),
+
+    #[error("unterminated quote")]
+    UnterminatedQuote,
+}
+
+pub type FilterResult<T> = Result<T, FilterError>;
+
+/// Operators in the order they are tried, so `<=` is not read as `<`.
+const OPERATORS: [&str; 7] = ["<=", ">=", "<", ">", "=", ":", "~"];
+
+/// Parses a filter typed as space separated terms, every one of which has to hold:
+///
+/// ```text
+/// amount<-100 type:POS name~uber date>=2025-06-01 memo~"gift card" coffee
+/// ```
+///
+/// `:` compares text for equality and `~` looks for it anywhere, both ignoring case. Amounts
+/// and dates also take `<`, `<=`, `>`, `>=` and `=`. A bare word is looked for in the name and
+/// the memo. Values with spaces go in double quotes.
+pub fn parse_filter(expression: &str) -> FilterResult<TransactionFilter> {
+    let terms = split_terms(expression)?
+        .iter()
+        .map(|t| parse_term(t))
+        .collect::<FilterResult<Vec<_>>>()?;
+    Ok(TransactionFilter { terms })
+}
+
+fn split_terms(expression: &str) -> FilterResult<Vec<String>> {
+    let mut terms = Vec::new();
+    let mut term = String::new();
+    let mut quoted = false;
+    for c in expression.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !term.is_empty() {
+                    terms.push(std::mem::take(&mut term));
+                }
+            }
+            c => term.push(c),
+        }
+    }
+    if quoted {
+        return Err(FilterError::UnterminatedQuote);
+    }
+    if !term.is_empty() {
+        terms.push(term);
+    }
+    Ok(terms)
+}
+
+fn parse_term(term: &str) -> FilterResult<FilterTerm> {
+    let split = term
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .filter(|&i| i > 0)
+        .and_then(|i| {
+            let rest = &term[i..];
+            OPERATORS
+                .iter()
+                .find(|op| rest.starts_with(*op))
+                .map(|op| (&term[..i], *op, &rest[op.len()..]))
+        });
+    let Some((field, operator, value)) = split else {
+        return Ok(FilterTerm::Anywhere(TextMatch::Contains(term.to_string())));
+    };
+
+    let comparison = match operator {
+        "<" => Comparison::Less,
+        "<=" => Comparison::LessOrEqual,
+        ">" => Comparison::Greater,
+        ">=" => Comparison::GreaterOrEqual,
+        _ => Comparison::Equal,
+    };
+    let text_field = match field.to_lowercase().as_str() {
+        "amount" => {
+            if operator == "~" {
+                return Err(FilterError::InvalidAmount(term.to_string()));
+            }
+            let amount = value
+                .parse()
+                .map_err(|_| FilterError::InvalidAmount(term.to_string()))?;
+            return Ok(FilterTerm::Amount(comparison, amount));
+        }
+        "date" => {
+            let date = NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .filter(|_| operator != "~")
+                .ok_or_else(|| FilterError::InvalidDate(term.to_string()))?;
+            return Ok(FilterTerm::Date(comparison, date));
+        }
+        "name" => TextField::Name,
+        "memo" => TextField::Memo,
+        "type" => TextField::TrnType,
+        "fitid" => TextField::Fitid,
+        _ => return Err(FilterError::UnknownField(term.to_string())),
+    };
+
+    let text = match operator {
+        "~" => TextMatch::Contains(value.to_string()),
+        ":" | "=" => RegexBuilder::new(&format!("^{}$", regex::escape(value)))
+            .case_insensitive(true)
+            .build()
+            .map(TextMatch::Regex)
+            .expect("an escaped value is a valid regex"),
+        _ => return Err(FilterError::InvalidComparison(term.to_string())),
+    };
+    Ok(FilterTerm::Text(text_field, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{domain::entities::ofx::Transaction, test_support};
+    use pretty_assertions::assert_eq;
+
+    fn transaction(fitid: &str, trntype: &str, trnamt: f64, name: &str) -> Transaction {
+        Transaction {
+            trntype: trntype.to_string(),
+            name: Some(name.to_string()),
+            memo: Some("Gift card".to_string()),
+            ..test_support::transaction(fitid, (2025, 6, 2), trnamt)
+        }
+    }
+
+    #[test]
+    fn keeps_transactions_meeting_every_term() {
+        let transactions = [
+            transaction("1", "POS", -150.0, "UBER *TRIP"),
+            transaction("2", "POS", -100.0, "UBER *TRIP"),
+            transaction("3", "POSTED", -150.0, "Uber Eats"),
+            transaction("4", "POS", -180.0, "BAKERY"),
+        ];
+        let fitids = |expression: &str| -> Vec<String> {
+            let filter = parse_filter(expression).unwrap();
+            transactions
+                .iter()
+                .filter(|t| filter.matches(t))
+                .map(|t| t.fitid.clone())
+                .collect()
+        };
+
+        assert_eq!(fitids("amount<-100 type:POS name~uber"), vec!["1"]);
+        assert_eq!(fitids("amount<=-100 TYPE:pos name~uber"), vec!["1", "2"]);
+        assert_eq!(fitids("amount=-100"), vec!["2"]);
+        assert_eq!(fitids("memo~\"gift card\" eats"), vec!["3"]);
+        assert_eq!(fitids("date>2025-06-02"), Vec::<String>::new());
+        assert_eq!(fitids(""), vec!["1", "2", "3", "4"]);
+
+        assert_eq!(
+            parse_filter("amount<abc").unwrap_err(),
+            FilterError::InvalidAmount("amount<abc".to_string())
+        );
+        assert_eq!(
+            parse_filter("payee:x").unwrap_err(),
+            FilterError::UnknownField("payee:x".to_string())
+        );
+        assert_eq!(
+            parse_filter("name<x").unwrap_err(),
+            FilterError::InvalidComparison("name<x".to_string())
+        );
+    }
+}
@@ -1 +1,3 @@
 pub mod ofx_document_xml;
+pub mod source_element;
+pub mod xml_element;
@@ -23,7 +23,13 @@ pub struct OfxHeaderXml {
 #[serde(rename_all = "UPPERCASE")]
 pub struct OfxBodyXml {
     pub signonmsgsrsv1: SignOnMessageResponseV1Xml,
-    pub bankmsgsrsv1: BankMessageResponseV1Xml,
+    pub bankmsgsrsv1: Option<BankMessageResponseV1Xml>,
+    pub profmsgsrsv1: Option<ProfileMessageResponseV1Xml>,
+}
+
+impl OfxBodyXml {
+    /// Child tags the typed model reads. Anything else is preserved as an unknown element.
+    pub const TAGS: &'static [&'static str] = &["SIGNONMSGSRSV1", "BANKMSGSRSV1", "PROFMSGSRSV1"];
 }
 
 #[derive(Debug, Deserialize)]
@@ -32,12 +38,20 @@ pub struct SignOnMessageResponseV1Xml {
     pub sonrs: SignOnResponseXml,
 }
 
+impl SignOnMessageResponseV1Xml {
+    pub const TAGS: &'static [&'static str] = &["SONRS"];
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub struct BankMessageResponseV1Xml {
     pub stmttrnrs: Vec<StatementTransactionResponseXml>, // Matches the XML structure for multiple STMTTRNRS elements
 }
 
+impl BankMessageResponseV1Xml {
+    pub const TAGS: &'static [&'static str] = &["STMTTRNRS"];
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub struct SignOnResponseXml {
@@ -48,6 +62,10 @@ pub struct SignOnResponseXml {
     pub fi: Option<FinancialInstitutionXml>,
 }
 
+impl SignOnResponseXml {
+    pub const TAGS: &'static [&'static str] = &["STATUS", "DTSERVER", "LANGUAGE", "DTPROFUP", "FI"];
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub struct StatusXml {
@@ -56,6 +74,10 @@ pub struct StatusXml {
     pub message: Option<String>,
 }
 
+impl StatusXml {
+    pub const TAGS: &'static [&'static str] = &["CODE", "SEVERITY", "MESSAGE"];
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub struct FinancialInstitutionXml {
@@ -63,6 +85,10 @@ pub struct FinancialInstitutionXml {
     pub fid: Option<String>,
 }
 
+impl FinancialInstitutionXml {
+    pub const TAGS: &'static [&'static str] = &["ORG", "FID"];
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub struct StatementTransactionResponseXml {
@@ -71,6 +97,10 @@ pub struct StatementTransactionResponseXml {
     pub stmtrs: StatementResponseXml,
 }
 
+impl StatementTransactionResponseXml {
+    pub const TAGS: &'static [&'static str] = &["TRNUID", "STATUS", "STMTRS"];
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub struct StatementResponseXml {
@@ -81,6 +111,16 @@ pub struct StatementResponseXml {
     pub availbal: Option<BalanceXml>,
 }
 
+impl StatementResponseXml {
+    pub const TAGS: &'static [&'static str] = &[
+        "CURDEF",
+        "BANKACCTFROM",
+        "BANKTRANLIST",
+        "LEDGERBAL",
+        "AVAILBAL",
+    ];
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub struct BankAccountFromXml {
@@ -89,14 +129,23 @@ pub struct BankAccountFromXml {
     pub accttype: String,
 }
 
+impl BankAccountFromXml {
+    pub const TAGS: &'static [&'static str] = &["BANKID", "ACCTID", "ACCTTYPE"];
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub struct BankTransactionListXml {
     pub dtstart: String,
     pub dtend: String,
+    #[serde(default)]
     pub stmttrn: Vec<TransactionXml>,
 }
 
+impl BankTransactionListXml {
+    pub const TAGS: &'static [&'static str] = &["DTSTART", "DTEND", "STMTTRN"];
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub struct BalanceXml {
@@ -104,6 +153,10 @@ pub struct BalanceXml {
     pub dtasof: String,
 }
 
+impl BalanceXml {
+    pub const TAGS: &'static [&'static str] = &["BALAMT", "DTASOF"];
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub struct TransactionXml {
@@ -113,4 +166,342 @@ pub struct TransactionXml {
     pub fitid: String,
     pub name: Option<String>,
     pub memo: Option<String>,
+    pub correctfitid: Option<String>,
+    pub correctaction: Option<String>,
+    pub currency: Option<CurrencyXml>,
+    pub origcurrency: Option<CurrencyXml>,
+}
+
+impl TransactionXml {
+    pub const TAGS: &'static [&'static str] = &[
+        "TRNTYPE",
+        "DTPOSTED",
+        "TRNAMT",
+        "FITID",
+        "CORRECTFITID",
+        "CORRECTACTION",
+        "NAME",
+        "MEMO",
+        "CURRENCY",
+        "ORIGCURRENCY",
+    ];
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub struct CurrencyXml {
+    pub currate: f64,
+    pub cursym: String,
+}
+
+impl CurrencyXml {
+    pub const TAGS: &'static [&'static str] = &["CURRATE", "CURSYM"];
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub struct ProfileMessageResponseV1Xml {
+    pub proftrnrs: Vec<ProfileTransactionResponseXml>,
+}
+
+impl ProfileMessageResponseV1Xml {
+    pub const TAGS: &'static [&'static str] = &["PROFTRNRS"];
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub struct ProfileTransactionResponseXml {
+    pub trnuid: String,
+    pub status: StatusXml,
+    pub profrs: Option<ProfileResponseXml>,
+}
+
+impl ProfileTransactionResponseXml {
+    pub const TAGS: &'static [&'static str] = &["TRNUID", "STATUS", "PROFRS"];
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub struct ProfileResponseXml {
+    pub msgsetlist: MessageSetListXml,
+    pub signoninfolist: SignonInfoListXml,
+    pub dtprofup: String,
+    pub finame: String,
+    pub addr1: String,
+    pub addr2: Option<String>,
+    pub addr3: Option<String>,
+    pub city: String,
+    pub state: String,
+    pub postalcode: String,
+    pub country: Option<String>,
+    pub csphone: Option<String>,
+    pub tsphone: Option<String>,
+    pub faxphone: Option<String>,
+    pub url: Option<String>,
+    pub email: Option<String>,
+}
+
+impl ProfileResponseXml {
+    pub const TAGS: &'static [&'static str] = &[
+        "MSGSETLIST",
+        "SIGNONINFOLIST",
+        "DTPROFUP",
+        "FINAME",
+        "ADDR1",
+        "ADDR2",
+        "ADDR3",
+        "CITY",
+        "STATE",
+        "POSTALCODE",
+        "COUNTRY",
+        "CSPHONE",
+        "TSPHONE",
+        "FAXPHONE",
+        "URL",
+        "EMAIL",
+    ];
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub struct MessageSetListXml {
+    pub signonmsgset: Option<MessageSetXml>,
+    pub signupmsgset: Option<MessageSetXml>,
+    pub bankmsgset: Option<MessageSetXml>,
+    pub creditcardmsgset: Option<MessageSetXml>,
+    pub invstmtmsgset: Option<MessageSetXml>,
+    pub interxfermsgset: Option<MessageSetXml>,
+    pub wirexfermsgset: Option<MessageSetXml>,
+    pub billpaymsgset: Option<MessageSetXml>,
+    pub emailmsgset: Option<MessageSetXml>,
+    pub seclistmsgset: Option<MessageSetXml>,
+    pub profmsgset: Option<MessageSetXml>,
+}
+
+impl MessageSetListXml {
+    pub const TAGS: &'static [&'static str] = &[
+        "SIGNONMSGSET",
+        "SIGNUPMSGSET",
+        "BANKMSGSET",
+        "CREDITCARDMSGSET",
+        "INVSTMTMSGSET",
+        "INTERXFERMSGSET",
+        "WIREXFERMSGSET",
+        "BILLPAYMSGSET",
+        "EMAILMSGSET",
+        "SECLISTMSGSET",
+        "PROFMSGSET",
+    ];
+
+    /// Takes the wrapper read for `tag`, one of `TAGS`.
+    pub fn take(&mut self, tag: &str) -> Option<MessageSetXml> {
+        match tag {
+            "SIGNONMSGSET" => self.signonmsgset.take(),
+            "SIGNUPMSGSET" => self.signupmsgset.take(),
+            "BANKMSGSET" => self.bankmsgset.take(),
+            "CREDITCARDMSGSET" => self.creditcardmsgset.take(),
+            "INVSTMTMSGSET" => self.invstmtmsgset.take(),
+            "INTERXFERMSGSET" => self.interxfermsgset.take(),
+            "WIREXFERMSGSET" => self.wirexfermsgset.take(),
+            "BILLPAYMSGSET" => self.billpaymsgset.take(),
+            "EMAILMSGSET" => self.emailmsgset.take(),
+            "SECLISTMSGSET" => self.seclistmsgset.take(),
+            "PROFMSGSET" => self.profmsgset.take(),
+            _ => None,
+        }
+    }
+}
+
+/// Wrapper such as `<BANKMSGSET>`, holding one `<...MSGSETVn>` element per supported version.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageSetXml {
+    #[serde(rename = "$value")]
+    pub versions: Vec<MessageSetVersionXml>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub struct MessageSetVersionXml {
+    pub msgsetcore: MessageSetCoreXml,
+    pub closingavail: Option<String>,
+}
+
+impl MessageSetVersionXml {
+    pub const TAGS: &'static [&'static str] = &["MSGSETCORE", "CLOSINGAVAIL"];
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub struct MessageSetCoreXml {
+    pub ver: u32,
+    pub url: String,
+    pub ofxsec: String,
+    pub transpsec: String,
+    pub signonrealm: String,
+    pub language: Vec<String>,
+    pub syncmode: String,
+    pub respfileer: String,
+    pub spname: Option<String>,
+}
+
+impl MessageSetCoreXml {
+    pub const TAGS: &'static [&'static str] = &[
+        "VER",
+        "URL",
+        "OFXSEC",
+        "TRANSPSEC",
+        "SIGNONREALM",
+        "LANGUAGE",
+        "SYNCMODE",
+        "RESPFILEER",
+        "SPNAME",
+    ];
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub struct SignonInfoListXml {
+    #[serde(default)]
+    pub signoninfo: Vec<SignonInfoXml>,
+}
+
+impl SignonInfoListXml {
+    pub const TAGS: &'static [&'static str] = &["SIGNONINFO"];
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub struct SignonInfoXml {
+    pub signonrealm: String,
+    pub min: u32,
+    pub max: u32,
+    pub chartype: String,
+    pub casesen: String,
+    pub special: String,
+    pub spaces: String,
+    pub pinch: String,
+    pub chgpinfirst: Option<String>,
+}
+
+impl SignonInfoXml {
+    pub const TAGS: &'static [&'static str] = &[
+        "SIGNONREALM",
+        "MIN",
+        "MAX",
+        "CHARTYPE",
+        "CASESEN",
+        "SPECIAL",
+        "SPACES",
+        "PINCH",
+        "CHGPINFIRST",
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde::de::{self, Deserializer, Visitor, value::Error};
+
+    /// Deserializer that records the field names a struct asks for and reads nothing.
+    struct FieldNames(&'static [&'static str]);
+
+    impl<'de> Deserializer<'de> for &mut FieldNames {
+        type Error = Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+            Err(de::Error::custom("only structs have field names"))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _: &'static str,
+            fields: &'static [&'static str],
+            _: V,
+        ) -> Result<V::Value, Error> {
+            self.0 = fields;
+            Err(de::Error::custom("field names recorded"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+            option unit unit_struct newtype_struct seq tuple tuple_struct map enum identifier
+            ignored_any
+        }
+    }
+
+    /// The tags serde reads for `T`, sorted.
+    fn modelled<'de, T: Deserialize<'de>>() -> Vec<&'static str> {
+        let mut names = FieldNames(&[]);
+        let _ = T::deserialize(&mut names);
+        let mut tags = names.0.to_vec();
+        tags.sort_unstable();
+        tags
+    }
+
+    fn sorted(tags: &[&'static str]) -> Vec<&'static str> {
+        let mut tags = tags.to_vec();
+        tags.sort_unstable();
+        tags
+    }
+
+    /// A field added to a model but not to its `TAGS` would be read and also kept as unknown.
+    #[test]
+    fn tags_list_every_modelled_field() {
+        let models = [
+            (OfxBodyXml::TAGS, modelled::<OfxBodyXml>()),
+            (
+                SignOnMessageResponseV1Xml::TAGS,
+                modelled::<SignOnMessageResponseV1Xml>(),
+            ),
+            (
+                BankMessageResponseV1Xml::TAGS,
+                modelled::<BankMessageResponseV1Xml>(),
+            ),
+            (SignOnResponseXml::TAGS, modelled::<SignOnResponseXml>()),
+            (StatusXml::TAGS, modelled::<StatusXml>()),
+            (
+                FinancialInstitutionXml::TAGS,
+                modelled::<FinancialInstitutionXml>(),
+            ),
+            (
+                StatementTransactionResponseXml::TAGS,
+                modelled::<StatementTransactionResponseXml>(),
+            ),
+            (
+                StatementResponseXml::TAGS,
+                modelled::<StatementResponseXml>(),
+            ),
+            (BankAccountFromXml::TAGS, modelled::<BankAccountFromXml>()),
+            (
+                BankTransactionListXml::TAGS,
+                modelled::<BankTransactionListXml>(),
+            ),
+            (BalanceXml::TAGS, modelled::<BalanceXml>()),
+            (TransactionXml::TAGS, modelled::<TransactionXml>()),
+            (CurrencyXml::TAGS, modelled::<CurrencyXml>()),
+            (
+                ProfileMessageResponseV1Xml::TAGS,
+                modelled::<ProfileMessageResponseV1Xml>(),
+            ),
+            (
+                ProfileTransactionResponseXml::TAGS,
+                modelled::<ProfileTransactionResponseXml>(),
+            ),
+            (ProfileResponseXml::TAGS, modelled::<ProfileResponseXml>()),
+            (MessageSetListXml::TAGS, modelled::<MessageSetListXml>()),
+            (
+                MessageSetVersionXml::TAGS,
+                modelled::<MessageSetVersionXml>(),
+            ),
+            (MessageSetCoreXml::TAGS, modelled::<MessageSetCoreXml>()),
+            (SignonInfoListXml::TAGS, modelled::<SignonInfoListXml>()),
+            (SignonInfoXml::TAGS, modelled::<SignonInfoXml>()),
+        ];
+
+        for (tags, modelled) in models {
+            assert!(!modelled.is_empty());
+            assert_eq!(sorted(tags), modelled);
+        }
+    }
 }
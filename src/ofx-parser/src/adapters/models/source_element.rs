@@ -0,0 +1,180 @@
+use crate::domain::entities::ofx::SourceElement;
+
+/// Splits a whole file, header included, into the elements it is written as.
+///
+/// Unlike the typed models and [`super::xml_element::XmlElement`] this keeps every byte, so it
+/// is read by hand rather than through an XML reader: SGML elements without an end tag are
+/// closed at the next tag once they hold text, end tags close whatever was left open inside
+/// them, and anything that is not an element, such as the header or a comment, is kept as text.
+pub fn read_source(content: &str) -> SourceElement {
+    // Open elements, each with whether it holds text other than whitespace and comments
+    let mut stack = vec![(
+        SourceElement {
+            text: vec![String::new()],
+            ..SourceElement::default()
+        },
+        false,
+    )];
+    let mut rest = content;
+
+    while let Some(open) = rest.find('<') {
+        push_data(&mut stack, &rest[..open]);
+        rest = &rest[open..];
+        let Some(length) = token_length(rest) else {
+            break;
+        };
+        let (token, after) = rest.split_at(length);
+        rest = after;
+
+        if token.starts_with("<!") || token.starts_with("<?") {
+            push_text(&mut stack, token);
+        } else if let Some(name) = token.strip_prefix("</") {
+            let tag = tag_name(name);
+            match stack.iter().skip(1).rposition(|(e, _)| e.tag == tag) {
+                Some(index) => {
+                    // Everything opened after the element was left open, as SGML does
+                    while stack.len() > index + 2 {
+                        close(&mut stack, None);
+                    }
+                    close(&mut stack, Some(token));
+                }
+                None => push_text(&mut stack, token),
+            }
+        } else {
+            // An element that got text before any child is an SGML leaf without an end tag
+            if stack.len() > 1
+                && stack
+                    .last()
+                    .is_some_and(|(e, data)| *data && e.children.is_empty())
+            {
+                close(&mut stack, None);
+            }
+            stack.push((
+                SourceElement {
+                    tag: tag_name(&token[1..]).to_string(),
+                    start: token.to_string(),
+                    text: vec![String::new()],
+                    children: Vec::new(),
+                    end: None,
+                },
+                false,
+            ));
+            if token.ends_with("/>") {
+                close(&mut stack, None);
+            }
+        }
+    }
+    push_text(&mut stack, rest);
+
+    while stack.len() > 1 {
+        close(&mut stack, None);
+    }
+    stack.pop().map(|(file, _)| file).unwrap_or_default()
+}
+
+/// Length of the tag, comment or declaration `rest` starts with, none when it never ends.
+fn token_length(rest: &str) -> Option<usize> {
+    for (open, close) in [("<!--", "-->"), ("<![CDATA[", "]]>"), ("<?", "?>")] {
+        if rest.starts_with(open) {
+            return rest.find(close).map(|i| i + close.len());
+        }
+    }
+
+    let mut quote = None;
+    for (i, c) in rest.char_indices().skip(1) {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '>') => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn tag_name(token: &str) -> &str {
+    let end = token
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .unwrap_or(token.len());
+    &token[..end]
+}
+
+/// Adds character data, as opposed to markup kept as text.
+fn push_data(stack: &mut [(SourceElement, bool)], text: &str) {
+    if let Some((_, data)) = stack.last_mut() {
+        *data |= !text.trim().is_empty();
+    }
+    push_text(stack, text);
+}
+
+fn push_text(stack: &mut [(SourceElement, bool)], text: &str) {
+    if let Some(piece) = stack.last_mut().and_then(|(e, _)| e.text.last_mut()) {
+        piece.push_str(text);
+    }
+}
+
+fn close(stack: &mut Vec<(SourceElement, bool)>, end: Option<&str>) {
+    if let Some((mut element, _)) = stack.pop() {
+        element.end = end.map(str::to_string);
+        if let Some((parent, _)) = stack.last_mut() {
+            parent.children.push(element);
+            parent.text.push(String::new());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn reads_back_every_byte() {
+        let content = "OFXHEADER:100\r\nDATA:OFXSGML\r\n\r\n<OFX>\n  <!-- note -->\n  <A  x='1>2' y=\"b\">\n    <B>&amp; text </B >\n    <C/>\n    <D>sgml\n  </A>\n</OFX>\n";
+
+        let source = read_source(content);
+
+        assert_eq!(source.to_string(), content);
+        let ofx = &source.children[0];
+        assert_eq!(ofx.tag, "OFX");
+        let a = &ofx.children[0];
+        assert_eq!(a.start, "<A  x='1>2' y=\"b\">");
+        assert_eq!(
+            a.children
+                .iter()
+                .map(|c| (c.tag.as_str(), c.end.as_deref()))
+                .collect::<Vec<_>>(),
+            vec![("B", Some("</B >")), ("C", None), ("D", None)]
+        );
+        assert_eq!(a.children[0].text, vec!["&amp; text ".to_string()]);
+    }
+
+    #[test]
+    fn closes_sgml_leaves_at_the_next_tag() {
+        let content = "<STMTTRN><TRNAMT>-10.00\n<FITID>1<NAME>Store</NAME></STMTTRN>";
+
+        let source = read_source(content);
+
+        assert_eq!(source.to_string(), content);
+        let stmttrn = &source.children[0];
+        assert_eq!(
+            stmttrn
+                .children
+                .iter()
+                .map(|c| (c.tag.as_str(), c.text.concat(), c.end.is_some()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("TRNAMT", "-10.00\n".to_string(), false),
+                ("FITID", "1".to_string(), false),
+                ("NAME", "Store".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_unfinished_tags_as_text() {
+        let content = "<OFX><A>1</A><B";
+
+        assert_eq!(read_source(content).to_string(), content);
+    }
+}
@@ -0,0 +1,147 @@
+use quick_xml::{
+    Reader,
+    escape::{resolve_predefined_entity, unescape},
+    events::{BytesStart, Event},
+};
+
+/// Schema-less view of the OFX body, used to recover elements the typed models do not name.
+///
+/// `value` is the text trimmed and unescaped, dropped when only whitespace, while `text` keeps
+/// it as written along with comments and processing instructions.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct XmlElement {
+    pub tag: String,
+    pub value: Option<String>,
+    pub text: Option<String>,
+    /// Names and values as written.
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<XmlElement>,
+}
+
+impl XmlElement {
+    pub fn parse(xml: &str) -> Result<XmlElement, quick_xml::Error> {
+        let mut reader = Reader::from_str(xml);
+
+        // Sentinel root so that the document element ends up as its only child
+        let mut stack: Vec<XmlElement> = vec![XmlElement::default()];
+
+        loop {
+            match reader.read_event()? {
+                Event::Start(start) => stack.push(Self::start(&start)?),
+                Event::Empty(start) => {
+                    let element = Self::start(&start)?;
+                    if let Some(parent) = stack.last_mut() {
+                        parent.children.push(element);
+                    }
+                }
+                Event::End(_) if stack.len() > 1 => Self::close(&mut stack),
+                Event::Text(text) => {
+                    let raw = text.decode().map_err(quick_xml::Error::Encoding)?;
+                    let text = unescape(&raw)
+                        .map_err(quick_xml::Error::Escape)?
+                        .into_owned();
+                    Self::append_text(&mut stack, &text, &raw);
+                }
+                Event::CData(data) => {
+                    let text = data.decode().map_err(quick_xml::Error::Encoding)?;
+                    Self::append_text(&mut stack, &text, &format!("<![CDATA[{text}]]>"));
+                }
+                Event::Comment(comment) => {
+                    let text = comment.decode().map_err(quick_xml::Error::Encoding)?;
+                    Self::append_raw(&mut stack, &format!("<!--{text}-->"));
+                }
+                Event::PI(instruction) => {
+                    let text = String::from_utf8_lossy(&instruction);
+                    Self::append_raw(&mut stack, &format!("<?{text}?>"));
+                }
+                Event::GeneralRef(reference) => {
+                    let name = reference.decode().map_err(quick_xml::Error::Encoding)?;
+                    let raw = format!("&{name};");
+                    let text = match reference.resolve_char_ref()? {
+                        Some(c) => c.to_string(),
+                        None => resolve_predefined_entity(&name)
+                            .map_or_else(|| raw.clone(), str::to_string),
+                    };
+                    Self::append_text(&mut stack, &text, &raw);
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        // Unclosed elements are folded into their parents so nothing is lost
+        while stack.len() > 1 {
+            Self::close(&mut stack);
+        }
+
+        let mut root = stack.pop().unwrap_or_default();
+        Ok(if root.children.len() == 1 {
+            root.children.remove(0)
+        } else {
+            root
+        })
+    }
+
+    pub fn new(tag: impl Into<String>) -> Self {
+        XmlElement {
+            tag: tag.into(),
+            value: None,
+            text: None,
+            attributes: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    fn start(start: &BytesStart) -> Result<XmlElement, quick_xml::Error> {
+        let mut element = XmlElement::new(String::from_utf8_lossy(start.name().as_ref()));
+        for attribute in start.attributes() {
+            let attribute = attribute.map_err(quick_xml::Error::InvalidAttr)?;
+            element.attributes.push((
+                String::from_utf8_lossy(attribute.key.as_ref()).into_owned(),
+                String::from_utf8_lossy(&attribute.value).into_owned(),
+            ));
+        }
+        Ok(element)
+    }
+
+    pub fn child(&self, tag: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|c| c.tag == tag)
+    }
+
+    pub fn children_named<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children.iter().filter(move |c| c.tag == tag)
+    }
+
+    /// Follows a `/`-separated path of child tags, always taking the first match.
+    pub fn find(&self, path: &str) -> Option<&XmlElement> {
+        path.split('/')
+            .try_fold(self, |element, tag| element.child(tag))
+    }
+
+    /// Pops the innermost open element, trims its text and attaches it to its parent.
+    fn close(stack: &mut Vec<XmlElement>) {
+        if let Some(mut element) = stack.pop() {
+            element.value = element
+                .value
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty());
+            if let Some(parent) = stack.last_mut() {
+                parent.children.push(element);
+            }
+        }
+    }
+
+    fn append_text(stack: &mut [XmlElement], text: &str, raw: &str) {
+        if let Some(current) = stack.last_mut() {
+            current.value.get_or_insert_with(String::new).push_str(text);
+        }
+        Self::append_raw(stack, raw);
+    }
+
+    /// Adds to the text as written only, for what is not part of the value.
+    fn append_raw(stack: &mut [XmlElement], raw: &str) {
+        if let Some(current) = stack.last_mut() {
+            current.text.get_or_insert_with(String::new).push_str(raw);
+        }
+    }
+}
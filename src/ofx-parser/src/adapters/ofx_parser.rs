@@ -1,19 +1,29 @@
 use crate::domain::entities::ofx::{
-    Balance, BankAccount, BankTransactionList, FinancialInstitution, OfxBody, OfxDocument,
-    OfxHeader, SignonResponse, StatementResponse, StatementTransactionResponse, Status,
-    Transaction,
+    Balance, BankAccount, BankTransactionList, CorrectAction, Currency, FinancialInstitution,
+    IntuitExtensions, OfxBody, OfxDateTime, OfxDocument, OfxHeader, SignonResponse,
+    StatementResponse, StatementTransactionResponse, Status, Transaction, UnknownElement,
 };
-use chrono::{DateTime, FixedOffset};
+use crate::domain::entities::profile::{
+    CharType, MessageSet, MessageSetKind, ProfileResponse, ProfileTransactionResponse, SignonInfo,
+};
+use chrono::{FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeDelta, TimeZone, Utc};
+use chrono_tz::Tz;
 use log::{debug, error, info, warn};
-use quick_xml::de::from_str;
+use quick_xml::{Reader, de::from_str, events::Event};
 use regex::Regex;
+use std::{collections::BTreeMap, fmt};
 use thiserror::Error;
 
 use super::models::ofx_document_xml::{
-    BalanceXml, BankAccountFromXml, BankTransactionListXml, FinancialInstitutionXml, OfxBodyXml,
-    OfxDocumentXml, OfxHeaderXml, SignOnResponseXml, StatementResponseXml,
-    StatementTransactionResponseXml, StatusXml, TransactionXml,
+    BalanceXml, BankAccountFromXml, BankMessageResponseV1Xml, BankTransactionListXml, CurrencyXml,
+    FinancialInstitutionXml, MessageSetCoreXml, MessageSetListXml, MessageSetVersionXml,
+    OfxBodyXml, OfxDocumentXml, OfxHeaderXml, ProfileMessageResponseV1Xml, ProfileResponseXml,
+    ProfileTransactionResponseXml, SignOnMessageResponseV1Xml, SignOnResponseXml,
+    SignonInfoListXml, SignonInfoXml, StatementResponseXml, StatementTransactionResponseXml,
+    StatusXml, TransactionXml,
 };
+use super::models::{source_element::read_source, xml_element::XmlElement};
+use super::timezone::{is_ambiguous, resolve_zone};
 
 #[derive(Error, Debug)]
 pub enum OfxError {
@@ -23,6 +33,13 @@ pub enum OfxError {
     #[error("XML parsing error: {0}")]
     Xml(#[from] quick_xml::DeError),
 
+    #[error("XML parsing error at line {line}, column {column}: {source}")]
+    XmlAt {
+        line: usize,
+        column: usize,
+        source: quick_xml::DeError,
+    },
+
     #[error("Invalid OFX version: {0}")]
     InvalidVersion(String),
 
@@ -41,10 +58,87 @@ pub enum OfxError {
 
 pub type OfxResult<T> = Result<T, OfxError>;
 
+impl OfxError {
+    /// Line and column in the parsed content the error was found at, both starting at 1. The
+    /// column counts characters, not bytes.
+    pub fn location(&self) -> Option<(usize, usize)> {
+        match self {
+            OfxError::XmlAt { line, column, .. } => Some((*line, *column)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// When set, every date in the document is re-expressed in this zone after parsing.
+    pub timezone: Option<Tz>,
+    /// Work around common producer mistakes, such as SGML elements without end tags or an
+    /// unexpected version, reporting each as a warning instead of failing.
+    pub lenient: bool,
+}
+
+/// A problem lenient parsing worked around.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseWarning {
+    /// Line in the parsed content, starting at 1, when the problem is tied to one.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {line}: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+/// Collects warnings in lenient mode, where it lets errors through otherwise.
+struct Warnings {
+    lenient: bool,
+    list: Vec<ParseWarning>,
+}
+
+impl Warnings {
+    fn tolerate(&mut self, line: Option<usize>, error: OfxError) -> OfxResult<()> {
+        if !self.lenient {
+            return Err(error);
+        }
+        warn!("Tolerating {error}");
+        self.list.push(ParseWarning {
+            line,
+            message: error.to_string(),
+        });
+        Ok(())
+    }
+}
+
 pub struct OfxParser;
 
 impl OfxParser {
     pub fn parse_string(content: &str) -> OfxResult<OfxDocument> {
+        Self::parse_string_with_options(content, &ParseOptions::default())
+    }
+
+    pub fn parse_string_with_options(
+        content: &str,
+        options: &ParseOptions,
+    ) -> OfxResult<OfxDocument> {
+        Self::parse_string_with_warnings(content, options).map(|(document, _)| document)
+    }
+
+    /// Parses like [`Self::parse_string_with_options`], also returning what lenient parsing had
+    /// to work around. Without `lenient` there are never any warnings.
+    pub fn parse_string_with_warnings(
+        content: &str,
+        options: &ParseOptions,
+    ) -> OfxResult<(OfxDocument, Vec<ParseWarning>)> {
+        let mut warnings = Warnings {
+            lenient: options.lenient,
+            list: Vec::new(),
+        };
         info!(
             "Parsing OFX content string. Length: {} bytes",
             content.len()
@@ -54,33 +148,350 @@ impl OfxParser {
         let xml_start_regex = Regex::new(r"<\?xml.*\?>|<OFX>").unwrap();
         let xml_start_pos = xml_start_regex.find(content).map_or(0, |mat| mat.start());
 
-        if xml_start_pos == 0 {
+        let (header_content, body_content) = content.split_at(xml_start_pos);
+
+        let header: OfxHeader = if xml_start_pos == 0 {
             error!(
                 "No valid header found. The content starts with XML. Content: {}",
                 content.trim()
             );
-            return Err(OfxError::InvalidContent(
-                "No valid header found".to_string(),
-            ));
-        }
-
-        let (header_content, body_content) = content.split_at(xml_start_pos);
-
-        let header: OfxHeader = Self::parse_header(header_content)?;
+            warnings.tolerate(
+                Some(1),
+                OfxError::InvalidContent("No valid header found".to_string()),
+            )?;
+            OfxHeader::default()
+        } else {
+            Self::parse_header(header_content, &mut warnings)?
+        };
         debug!("Successfully parsed header: {header:#?}");
 
+        // Lines before the body, to report positions in the whole content
+        let body_line = header_content.matches('\n').count();
+        let closed;
+        let body_content = if options.lenient {
+            let (body, first) = Self::close_sgml_elements(body_content);
+            if let Some((line, count)) = first {
+                warnings.list.push(ParseWarning {
+                    line: Some(body_line + line),
+                    message: format!("Closed {count} elements that had no end tag, as in SGML"),
+                });
+            }
+            closed = body;
+            closed.as_str()
+        } else {
+            body_content
+        };
+
         // The body is XML content, starting from the second part
-        let xml_body: OfxBodyXml = Self::parse_xml_body(body_content)?;
+        let xml_body: OfxBodyXml = Self::parse_xml_body(body_content, body_line)?;
 
         let xml_document: OfxDocumentXml = OfxDocumentXml {
             header,
             body: xml_body,
         };
 
-        Ok(OfxDocument::from(xml_document))
+        let mut document = OfxDocument::from(xml_document);
+
+        let tree: XmlElement = XmlElement::parse(body_content)
+            .inspect_err(|e| error!("Unable to build element tree: {e:?}"))
+            .map_err(|e| OfxError::Xml(e.into()))?;
+        Self::attach_extensions(&mut document, &tree);
+        Self::attach_unknown_elements(&mut document, &tree);
+        document.source = Some(read_source(content));
+
+        if let Some(tz) = &options.timezone {
+            debug!("Normalizing dates to {tz}");
+            document.normalize_timezone(tz);
+        }
+
+        Ok((document, warnings.list))
+    }
+
+    /// Adds the end tags SGML lets elements with a value leave out, returning the fixed body
+    /// along with the line of the first one added and how many were.
+    fn close_sgml_elements(body: &str) -> (String, Option<(usize, usize)>) {
+        let tag_regex = Regex::new(r"<(/?)([A-Za-z0-9._]+)[^>]*>").unwrap();
+        let tags: Vec<_> = tag_regex.captures_iter(body).collect();
+
+        let mut fixed = String::with_capacity(body.len());
+        let mut copied = 0;
+        let mut first = None;
+        let mut count = 0;
+        for (i, tag) in tags.iter().enumerate() {
+            let whole = tag.get(0).unwrap();
+            let is_end = !tag[1].is_empty();
+            let text_end = tags
+                .get(i + 1)
+                .map_or(body.len(), |n| n.get(0).unwrap().start());
+            let text = &body[whole.end()..text_end];
+            let closed_next = tags
+                .get(i + 1)
+                .is_some_and(|n| !n[1].is_empty() && n[2] == tag[2]);
+
+            if is_end || text.trim().is_empty() || closed_next {
+                continue;
+            }
+            let value_end = whole.end() + text.trim_end().len();
+            fixed.push_str(&body[copied..value_end]);
+            fixed.push_str(&format!("</{}>", &tag[2]));
+            copied = value_end;
+
+            count += 1;
+            first.get_or_insert_with(|| body[..whole.start()].matches('\n').count() + 1);
+        }
+        fixed.push_str(&body[copied..]);
+
+        (fixed, first.map(|line| (line, count)))
+    }
+
+    /// Copies `INTU.*` vendor elements, which the typed models skip, onto the domain aggregates.
+    fn attach_extensions(document: &mut OfxDocument, tree: &XmlElement) {
+        if let Some(sonrs) = tree.find("SIGNONMSGSRSV1/SONRS") {
+            document.body.sign_on_response.extensions = Self::intuit_extensions(sonrs);
+        }
+
+        let statements = tree
+            .child("BANKMSGSRSV1")
+            .into_iter()
+            .flat_map(|b| b.children_named("STMTTRNRS"))
+            .filter_map(|t| t.child("STMTRS"));
+
+        for (message, stmtrs) in document.body.bank_msgs.iter_mut().zip(statements) {
+            message.stmtrs.extensions = Self::intuit_extensions(stmtrs);
+        }
+    }
+
+    fn intuit_extensions(element: &XmlElement) -> IntuitExtensions {
+        let mut extensions = IntuitExtensions::default();
+
+        for child in element
+            .children
+            .iter()
+            .filter(|c| c.tag.starts_with("INTU."))
+        {
+            match child.tag.as_str() {
+                "INTU.BID" => extensions.bid = child.value.clone(),
+                "INTU.USERID" => extensions.userid = child.value.clone(),
+                _ => Self::flatten_into(&mut extensions.other, &child.tag, child),
+            }
+        }
+
+        if !extensions.is_empty() {
+            debug!("Found Intuit extensions on {}: {extensions:?}", element.tag);
+        }
+        extensions
+    }
+
+    /// Keeps every element the typed models skip on the aggregate it was found in.
+    fn attach_unknown_elements(document: &mut OfxDocument, tree: &XmlElement) {
+        let body = &mut document.body;
+        body.unknown = Self::unknown_children(tree, OfxBodyXml::TAGS);
+
+        if let Some(signon) = tree.child("SIGNONMSGSRSV1") {
+            Self::attach_message_set_unknown_elements(
+                body,
+                signon,
+                SignOnMessageResponseV1Xml::TAGS,
+            );
+
+            if let Some(sonrs) = signon.child("SONRS") {
+                let response = &mut body.sign_on_response;
+                response.unknown =
+                    Self::unknown_non_intuit_children(sonrs, SignOnResponseXml::TAGS);
+                if let Some(status) = sonrs.child("STATUS") {
+                    response.status.unknown = Self::unknown_children(status, StatusXml::TAGS);
+                }
+                if let (Some(fi), Some(fi_xml)) = (response.fi.as_mut(), sonrs.child("FI")) {
+                    fi.unknown = Self::unknown_children(fi_xml, FinancialInstitutionXml::TAGS);
+                }
+            }
+        }
+
+        if let Some(bank) = tree.child("BANKMSGSRSV1") {
+            Self::attach_message_set_unknown_elements(body, bank, BankMessageResponseV1Xml::TAGS);
+
+            for (message, stmttrnrs) in body
+                .bank_msgs
+                .iter_mut()
+                .zip(bank.children_named("STMTTRNRS"))
+            {
+                Self::attach_statement_unknown_elements(message, stmttrnrs);
+            }
+        }
+
+        if let Some(profile) = tree.child("PROFMSGSRSV1") {
+            Self::attach_message_set_unknown_elements(
+                body,
+                profile,
+                ProfileMessageResponseV1Xml::TAGS,
+            );
+
+            for (message, proftrnrs) in body
+                .profile_msgs
+                .iter_mut()
+                .zip(profile.children_named("PROFTRNRS"))
+            {
+                Self::attach_profile_unknown_elements(message, proftrnrs);
+            }
+        }
+    }
+
+    fn attach_message_set_unknown_elements(
+        body: &mut OfxBody,
+        wrapper: &XmlElement,
+        known: &[&str],
+    ) {
+        let unknown = Self::unknown_children(wrapper, known);
+        if !unknown.is_empty() {
+            body.message_set_unknown
+                .insert(wrapper.tag.clone(), unknown);
+        }
+    }
+
+    fn attach_statement_unknown_elements(
+        message: &mut StatementTransactionResponse,
+        stmttrnrs: &XmlElement,
+    ) {
+        message.unknown = Self::unknown_children(stmttrnrs, StatementTransactionResponseXml::TAGS);
+        if let Some(status) = stmttrnrs.child("STATUS") {
+            message.status.unknown = Self::unknown_children(status, StatusXml::TAGS);
+        }
+
+        let Some(stmtrs) = stmttrnrs.child("STMTRS") else {
+            return;
+        };
+        let statement = &mut message.stmtrs;
+        statement.unknown = Self::unknown_non_intuit_children(stmtrs, StatementResponseXml::TAGS);
+
+        if let Some(account) = stmtrs.child("BANKACCTFROM") {
+            statement.bankacctfrom.unknown =
+                Self::unknown_children(account, BankAccountFromXml::TAGS);
+        }
+        if let (Some(balance), Some(xml)) =
+            (statement.ledgerbal.as_mut(), stmtrs.child("LEDGERBAL"))
+        {
+            balance.unknown = Self::unknown_children(xml, BalanceXml::TAGS);
+        }
+        if let (Some(balance), Some(xml)) = (statement.availbal.as_mut(), stmtrs.child("AVAILBAL"))
+        {
+            balance.unknown = Self::unknown_children(xml, BalanceXml::TAGS);
+        }
+        if let (Some(list), Some(xml)) = (
+            statement.banktranlist.as_mut(),
+            stmtrs.child("BANKTRANLIST"),
+        ) {
+            list.unknown = Self::unknown_children(xml, BankTransactionListXml::TAGS);
+            for (transaction, stmttrn) in list
+                .transactions
+                .iter_mut()
+                .zip(xml.children_named("STMTTRN"))
+            {
+                transaction.unknown = Self::unknown_children(stmttrn, TransactionXml::TAGS);
+                for (currency, tag) in [
+                    (transaction.currency.as_mut(), "CURRENCY"),
+                    (transaction.origcurrency.as_mut(), "ORIGCURRENCY"),
+                ] {
+                    if let (Some(currency), Some(xml)) = (currency, stmttrn.child(tag)) {
+                        currency.unknown = Self::unknown_children(xml, CurrencyXml::TAGS);
+                    }
+                }
+            }
+        }
+    }
+
+    fn attach_profile_unknown_elements(
+        message: &mut ProfileTransactionResponse,
+        proftrnrs: &XmlElement,
+    ) {
+        message.unknown = Self::unknown_children(proftrnrs, ProfileTransactionResponseXml::TAGS);
+        if let Some(status) = proftrnrs.child("STATUS") {
+            message.status.unknown = Self::unknown_children(status, StatusXml::TAGS);
+        }
+
+        let (Some(profile), Some(profrs)) = (message.profrs.as_mut(), proftrnrs.child("PROFRS"))
+        else {
+            return;
+        };
+        profile.unknown = Self::unknown_children(profrs, ProfileResponseXml::TAGS);
+
+        if let Some(list) = profrs.child("SIGNONINFOLIST") {
+            profile
+                .unknown
+                .extend(Self::unknown_children(list, SignonInfoListXml::TAGS));
+            for (info, xml) in profile
+                .signon_infos
+                .iter_mut()
+                .zip(list.children_named("SIGNONINFO"))
+            {
+                info.unknown = Self::unknown_children(xml, SignonInfoXml::TAGS);
+            }
+        }
+
+        if let Some(list) = profrs.child("MSGSETLIST") {
+            profile
+                .unknown
+                .extend(Self::unknown_children(list, MessageSetListXml::TAGS));
+            for (kind, tag) in MESSAGE_SET_TAGS {
+                let versions = list.child(tag).map_or(&[][..], |w| &w.children);
+                let sets = profile.message_sets.iter_mut().filter(|m| m.kind == kind);
+                for (set, version) in sets.zip(versions) {
+                    set.unknown = Self::unknown_children(version, MessageSetVersionXml::TAGS);
+                    if let Some(core) = version.child("MSGSETCORE") {
+                        set.unknown
+                            .extend(Self::unknown_children(core, MessageSetCoreXml::TAGS));
+                    }
+                }
+            }
+        }
+    }
+
+    fn unknown_children(element: &XmlElement, known: &[&str]) -> Vec<UnknownElement> {
+        element
+            .children
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| !known.contains(&c.tag.as_str()))
+            .map(|(position, c)| Self::unknown_element(&element.tag, position, c))
+            .inspect(|u| debug!("Preserving unknown element {} in {}", u.tag, u.parent))
+            .collect()
+    }
+
+    /// Like [`Self::unknown_children`], for aggregates whose `INTU.*` children already live in
+    /// their [`IntuitExtensions`].
+    fn unknown_non_intuit_children(element: &XmlElement, known: &[&str]) -> Vec<UnknownElement> {
+        Self::unknown_children(element, known)
+            .into_iter()
+            .filter(|u| !u.tag.starts_with("INTU."))
+            .collect()
+    }
+
+    fn unknown_element(parent: &str, position: usize, element: &XmlElement) -> UnknownElement {
+        UnknownElement {
+            parent: parent.to_string(),
+            position,
+            tag: element.tag.clone(),
+            value: element.value.clone(),
+            text: element.text.clone(),
+            attributes: element.attributes.clone(),
+            children: element
+                .children
+                .iter()
+                .enumerate()
+                .map(|(i, c)| Self::unknown_element(&element.tag, i, c))
+                .collect(),
+        }
     }
 
-    fn parse_header(header_content: &str) -> OfxResult<OfxHeader> {
+    fn flatten_into(map: &mut BTreeMap<String, String>, path: &str, element: &XmlElement) {
+        if let Some(value) = &element.value {
+            map.insert(path.to_string(), value.clone());
+        }
+        for child in &element.children {
+            Self::flatten_into(map, &format!("{path}/{}", child.tag), child);
+        }
+    }
+
+    fn parse_header(header_content: &str, warnings: &mut Warnings) -> OfxResult<OfxHeader> {
         debug!("Parsing OFX header. Content: {}", header_content.trim());
 
         let mut version = None;
@@ -91,7 +502,7 @@ impl OfxParser {
         let mut old_file_uid = None;
         let mut new_file_uid = None;
 
-        for line in header_content.lines() {
+        for (index, line) in header_content.lines().enumerate() {
             if line.trim().is_empty() {
                 continue; // Skip empty lines for robustness
             }
@@ -112,14 +523,20 @@ impl OfxParser {
                 "OFXHEADER" => {
                     if value != "100" {
                         error!("Unsupported OFXHEADER value: {value}. Expected '100'.");
-                        return Err(OfxError::InvalidVersion(value.to_string()));
+                        warnings.tolerate(
+                            Some(index + 1),
+                            OfxError::InvalidVersion(value.to_string()),
+                        )?;
                     }
                     debug!("OFXHEADER validated: {value}");
                 }
                 "VERSION" => {
                     if value != "102" {
                         error!("Unsupported OFX version: {value}. Expected '102'.");
-                        return Err(OfxError::InvalidVersion(value.to_string()));
+                        warnings.tolerate(
+                            Some(index + 1),
+                            OfxError::InvalidVersion(value.to_string()),
+                        )?;
                     }
                     version = Some(value.to_string());
                     debug!("VERSION set to: {value}");
@@ -158,7 +575,8 @@ impl OfxParser {
             Some(v) => v,
             None => {
                 error!("Missing required VERSION in header.");
-                return Err(OfxError::MissingHeader("VERSION".to_string()));
+                warnings.tolerate(None, OfxError::MissingHeader("VERSION".to_string()))?;
+                OfxHeader::default().version
             }
         };
 
@@ -173,7 +591,8 @@ impl OfxParser {
         })
     }
 
-    fn parse_xml_body(body: &str) -> OfxResult<OfxBodyXml> {
+    /// `body_line` is the number of lines before the body, for error locations.
+    fn parse_xml_body(body: &str, body_line: usize) -> OfxResult<OfxBodyXml> {
         info!(
             "Deserializing XML body. Sample: {:#?}",
             body.lines().collect::<Vec<_>>()
@@ -183,7 +602,20 @@ impl OfxParser {
             .inspect_err(|e| {
                 error!("XML deserialization failed: {e:?}. Body content may be malformed.")
             })
-            .map_err(OfxError::Xml)
+            .map_err(|e| match Self::syntax_error_position(body) {
+                Some(position) => {
+                    let before = &body[..position];
+                    let line = before.matches('\n').count();
+                    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+                    let column = before[line_start..].chars().count() + 1;
+                    OfxError::XmlAt {
+                        line: body_line + line + 1,
+                        column,
+                        source: e,
+                    }
+                }
+                None => OfxError::Xml(e),
+            })
             .inspect(|o| {
                 debug!("Successfully deserialized XML body: {o:#?}");
             })?;
@@ -191,47 +623,158 @@ impl OfxParser {
         Ok(ofx_xml)
     }
 
-    pub fn parse_custom_datetime(s: &str) -> Result<DateTime<FixedOffset>, String> {
-        // 1. Find the start of the timezone bracket '['
-        if let Some(tz_start_index) = s.find('[') {
-            // The datetime part: "20250604000000"
-            let datetime_part = &s[..tz_start_index];
+    /// Byte offset of the first XML syntax error in `body`, if there is one.
+    fn syntax_error_position(body: &str) -> Option<usize> {
+        let mut reader = Reader::from_str(body);
+        loop {
+            match reader.read_event() {
+                Ok(Event::Eof) => return None,
+                Ok(_) => {}
+                Err(_) => return usize::try_from(reader.error_position()).ok(),
+            }
+        }
+    }
 
-            // The inner timezone part: "-3:BRT"
-            let tz_part = &s[tz_start_index + 1..s.len() - 1];
+    /// Parses an OFX date such as `20250604000000[-3:BRT]`.
+    ///
+    /// The bracket may hold an hour offset, a zone name or both. Names are resolved through the
+    /// IANA database and win over a numeric offset that disagrees with them, unless the name is
+    /// an ambiguous abbreviation such as `IST`. Without a bracket the date is taken to be in GMT,
+    /// as the specification requires.
+    pub fn parse_custom_datetime(s: &str) -> Result<OfxDateTime, String> {
+        let s = s.trim();
 
-            // 2. Extract the numeric hour offset: "-3"
-            if let Some(hour_offset_str) = tz_part.split(':').next() {
-                let hour_offset: i32 = hour_offset_str
-                    .parse()
-                    .map_err(|e| format!("Unable to parse hour offset: {e:?}"))?;
+        // 1. Split "20250604000000[-3:BRT]" into the datetime and the bracket contents
+        let (datetime_part, tz_part) = match s.find('[') {
+            Some(tz_start_index) => (
+                &s[..tz_start_index],
+                Some(s[tz_start_index + 1..].trim_end_matches(']')),
+            ),
+            None => (s, None),
+        };
 
-                // 3. Format the offset into the `+hhmm` format required by `%z`
-                // e.g., -3 becomes "-0300"
-                let formatted_offset = format!("{hour_offset:+03}00");
+        let naive = Self::parse_naive_datetime(datetime_part)?;
 
-                // 4. Create the final, parsable string: "20250604000000-0300"
-                let parsable_string = format!("{datetime_part}{formatted_offset}");
+        // 2. The bracket is "-3:BRT", "-3" or "BRT"
+        let (offset_part, tz_name) = match tz_part.map(str::trim) {
+            Some(tz) => match tz.split_once(':') {
+                Some((offset, name)) => (Some(offset), Some(name.trim())),
+                None if tz.starts_with(['+', '-'])
+                    || tz.starts_with(|c: char| c.is_ascii_digit()) =>
+                {
+                    (Some(tz), None)
+                }
+                None => (None, Some(tz)),
+            },
+            None => (None, None),
+        };
+        let tz_name = tz_name.filter(|n| !n.is_empty());
 
-                // 5. Parse the string using the correct format
-                let format_str = "%Y%m%d%H%M%S%z";
-                let date_time: DateTime<FixedOffset> =
-                    DateTime::parse_from_str(&parsable_string, format_str)
-                        .map_err(|e| format!("Unble to parse processed date: {e:?}"))?;
+        let numeric_offset = offset_part
+            .map(str::trim)
+            .filter(|o| !o.is_empty())
+            .map(Self::parse_hour_offset)
+            .transpose()?;
+
+        // 3. Work out what offset the named zone had at that local time
+        let named_offset = tz_name.and_then(|name| {
+            let zone = resolve_zone(name);
+            if zone.is_none() {
+                debug!("Unable to resolve timezone name {name}");
+            }
+            zone.and_then(|z| z.offset_from_local_datetime(&naive).earliest())
+                .map(|o| o.fix())
+        });
 
-                return Ok(date_time);
+        let offset = match (numeric_offset, named_offset) {
+            (Some(numeric), Some(named))
+                if numeric != named && tz_name.is_some_and(is_ambiguous) =>
+            {
+                debug!("Offset {numeric} picks which zone {tz_name:?} means in {s}");
+                numeric
             }
+            (Some(numeric), Some(named)) if numeric != named => {
+                warn!(
+                    "Offset {numeric} disagrees with zone {tz_name:?} ({named}) in {s}. Using the zone."
+                );
+                named
+            }
+            (_, Some(named)) => named,
+            (Some(numeric), None) => numeric,
+            (None, None) => {
+                if let Some(name) = tz_name {
+                    warn!("Unknown timezone {name} in {s}. Assuming GMT.");
+                }
+                Utc.fix()
+            }
+        };
+
+        // 4. Attach the offset to the local time
+        let datetime = naive
+            .and_local_timezone(offset)
+            .single()
+            .ok_or_else(|| format!("Unable to apply offset {offset} to {naive}"))?;
+
+        Ok(OfxDateTime {
+            datetime,
+            tz_name: tz_name.map(str::to_string),
+            raw: Some(s.to_string()),
+        })
+    }
+
+    /// Accepts `YYYYMMDD`, `YYYYMMDDHHMM` and `YYYYMMDDHHMMSS`, the latter optionally followed
+    /// by `.XXX` milliseconds.
+    fn parse_naive_datetime(s: &str) -> Result<NaiveDateTime, String> {
+        let s = s.trim();
+        let (main, fraction) = s.split_once('.').unwrap_or((s, ""));
+
+        let naive = match main.len() {
+            8 => NaiveDate::parse_from_str(main, "%Y%m%d")
+                .map(|d| d.and_time(NaiveTime::MIN))
+                .map_err(|e| format!("Unable to parse date {s}: {e:?}"))?,
+            12 => NaiveDateTime::parse_from_str(main, "%Y%m%d%H%M")
+                .map_err(|e| format!("Unable to parse datetime {s}: {e:?}"))?,
+            14 => NaiveDateTime::parse_from_str(main, "%Y%m%d%H%M%S")
+                .map_err(|e| format!("Unable to parse datetime {s}: {e:?}"))?,
+            _ => return Err(format!("Invalid date format: {s}")),
+        };
+
+        if fraction.is_empty() {
+            return Ok(naive);
+        }
+
+        // A fraction of a second, so ".5" is 500 milliseconds
+        if fraction.len() > 9 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("Unable to parse fractional seconds in {s}"));
         }
+        let nanos: i64 = format!("{fraction:0<9}")
+            .parse()
+            .map_err(|e| format!("Unable to parse fractional seconds in {s}: {e:?}"))?;
+        Ok(naive + TimeDelta::nanoseconds(nanos))
+    }
 
-        Err("Invalid date format".into())
+    /// Hour offsets may be fractional, e.g. `+5.5` for India.
+    fn parse_hour_offset(s: &str) -> Result<FixedOffset, String> {
+        let hours: f64 = s
+            .parse()
+            .map_err(|e| format!("Unable to parse hour offset: {e:?}"))?;
+
+        FixedOffset::east_opt((hours * 3600.0).round() as i32)
+            .ok_or_else(|| format!("Hour offset out of range: {s}"))
+    }
+
+    /// OFX booleans are encoded as `Y`/`N`.
+    fn parse_yes_no(s: &str) -> bool {
+        s.trim().eq_ignore_ascii_case("Y")
     }
 }
 
 impl From<OfxDocumentXml> for OfxDocument {
     fn from(value: OfxDocumentXml) -> Self {
         OfxDocument {
-            header: value.header.into(),
+            header: value.header,
             body: value.body.into(),
+            source: None,
         }
     }
 }
@@ -254,12 +797,20 @@ impl From<OfxBodyXml> for OfxBody {
     fn from(value: OfxBodyXml) -> Self {
         OfxBody {
             sign_on_response: value.signonmsgsrsv1.sonrs.into(),
-            bank_msgs: value
-                .bankmsgsrsv1
-                .stmttrnrs
-                .iter()
-                .map(|i: &StatementTransactionResponseXml| i.into())
-                .collect(),
+            bank_msgs: value.bankmsgsrsv1.map_or(Vec::new(), |b| {
+                b.stmttrnrs
+                    .iter()
+                    .map(|i: &StatementTransactionResponseXml| i.into())
+                    .collect()
+            }),
+            profile_msgs: value.profmsgsrsv1.map_or(Vec::new(), |p| {
+                p.proftrnrs
+                    .into_iter()
+                    .map(|i: ProfileTransactionResponseXml| i.into())
+                    .collect()
+            }),
+            unknown: Vec::new(),
+            message_set_unknown: BTreeMap::new(),
         }
     }
 }
@@ -278,6 +829,8 @@ impl From<SignOnResponseXml> for SignonResponse {
                     .unwrap_or_default()
             }),
             fi: value.fi.map(|i: FinancialInstitutionXml| i.into()),
+            extensions: IntuitExtensions::default(),
+            unknown: Vec::new(),
         }
     }
 }
@@ -288,6 +841,7 @@ impl From<StatusXml> for Status {
             code: value.code,
             severity: value.severity,
             message: value.message,
+            unknown: Vec::new(),
         }
     }
 }
@@ -297,6 +851,7 @@ impl From<FinancialInstitutionXml> for FinancialInstitution {
         FinancialInstitution {
             org: value.org,
             fid: value.fid,
+            unknown: Vec::new(),
         }
     }
 }
@@ -307,6 +862,7 @@ impl From<&StatementTransactionResponseXml> for StatementTransactionResponse {
             trnuid: value.trnuid.clone(),
             status: value.status.clone().into(),
             stmtrs: value.stmtrs.clone().into(),
+            unknown: Vec::new(),
         }
     }
 }
@@ -319,6 +875,8 @@ impl From<StatementResponseXml> for StatementResponse {
             banktranlist: value.banktranlist.map(|i: BankTransactionListXml| i.into()),
             ledgerbal: value.ledgerbal.map(|i: BalanceXml| i.into()),
             availbal: value.availbal.map(|i: BalanceXml| i.into()),
+            extensions: IntuitExtensions::default(),
+            unknown: Vec::new(),
         }
     }
 }
@@ -329,6 +887,7 @@ impl From<BankAccountFromXml> for BankAccount {
             bankid: value.bankid,
             acctid: value.acctid,
             accttype: value.accttype,
+            unknown: Vec::new(),
         }
     }
 }
@@ -347,6 +906,7 @@ impl From<BankTransactionListXml> for BankTransactionList {
                 .iter()
                 .map(|t: &TransactionXml| t.into())
                 .collect(),
+            unknown: Vec::new(),
         }
     }
 }
@@ -358,6 +918,7 @@ impl From<BalanceXml> for Balance {
             dtasof: OfxParser::parse_custom_datetime(&value.dtasof)
                 .inspect_err(|e: &String| error!("{e}"))
                 .unwrap_or_default(),
+            unknown: Vec::new(),
         }
     }
 }
@@ -373,6 +934,154 @@ impl From<&TransactionXml> for Transaction {
             fitid: value.fitid.clone(),
             name: value.name.clone(),
             memo: value.memo.clone(),
+            correctfitid: value.correctfitid.clone(),
+            correctaction: value.correctaction.as_deref().and_then(|a| match a.trim() {
+                "REPLACE" => Some(CorrectAction::Replace),
+                "DELETE" => Some(CorrectAction::Delete),
+                other => {
+                    warn!(
+                        "Unknown CORRECTACTION {other} on {}. Ignoring.",
+                        value.fitid
+                    );
+                    None
+                }
+            }),
+            currency: value.currency.as_ref().map(Currency::from),
+            origcurrency: value.origcurrency.as_ref().map(Currency::from),
+            unknown: Vec::new(),
+        }
+    }
+}
+
+impl From<&CurrencyXml> for Currency {
+    fn from(value: &CurrencyXml) -> Self {
+        Currency {
+            currate: value.currate,
+            cursym: value.cursym.trim().to_uppercase(),
+            unknown: Vec::new(),
+        }
+    }
+}
+
+impl From<ProfileTransactionResponseXml> for ProfileTransactionResponse {
+    fn from(value: ProfileTransactionResponseXml) -> Self {
+        ProfileTransactionResponse {
+            trnuid: value.trnuid,
+            status: value.status.into(),
+            profrs: value.profrs.map(|i: ProfileResponseXml| i.into()),
+            unknown: Vec::new(),
+        }
+    }
+}
+
+impl From<ProfileResponseXml> for ProfileResponse {
+    fn from(value: ProfileResponseXml) -> Self {
+        ProfileResponse {
+            message_sets: value.msgsetlist.into(),
+            signon_infos: value
+                .signoninfolist
+                .signoninfo
+                .into_iter()
+                .map(|i: SignonInfoXml| i.into())
+                .collect(),
+            dtprofup: OfxParser::parse_custom_datetime(&value.dtprofup)
+                .inspect_err(|e: &String| error!("{e}"))
+                .unwrap_or_default(),
+            finame: value.finame,
+            address: [Some(value.addr1), value.addr2, value.addr3]
+                .into_iter()
+                .flatten()
+                .collect(),
+            city: value.city,
+            state: value.state,
+            postalcode: value.postalcode,
+            country: value.country,
+            csphone: value.csphone,
+            tsphone: value.tsphone,
+            faxphone: value.faxphone,
+            url: value.url,
+            email: value.email,
+            unknown: Vec::new(),
+        }
+    }
+}
+
+/// `MSGSETLIST` wrapper tags, in the order their message sets are listed in the domain model.
+const MESSAGE_SET_TAGS: [(MessageSetKind, &str); 11] = [
+    (MessageSetKind::Signon, "SIGNONMSGSET"),
+    (MessageSetKind::Signup, "SIGNUPMSGSET"),
+    (MessageSetKind::Bank, "BANKMSGSET"),
+    (MessageSetKind::CreditCard, "CREDITCARDMSGSET"),
+    (MessageSetKind::Investment, "INVSTMTMSGSET"),
+    (MessageSetKind::InterTransfer, "INTERXFERMSGSET"),
+    (MessageSetKind::WireTransfer, "WIREXFERMSGSET"),
+    (MessageSetKind::BillPay, "BILLPAYMSGSET"),
+    (MessageSetKind::Email, "EMAILMSGSET"),
+    (MessageSetKind::SecurityList, "SECLISTMSGSET"),
+    (MessageSetKind::Profile, "PROFMSGSET"),
+];
+
+impl From<MessageSetListXml> for Vec<MessageSet> {
+    fn from(mut value: MessageSetListXml) -> Self {
+        MESSAGE_SET_TAGS
+            .into_iter()
+            .flat_map(|(kind, tag)| {
+                value
+                    .take(tag)
+                    .map_or(Vec::new(), |s| s.versions)
+                    .into_iter()
+                    .map(move |v: MessageSetVersionXml| MessageSet::from((kind, v)))
+            })
+            .collect()
+    }
+}
+
+impl From<(MessageSetKind, MessageSetVersionXml)> for MessageSet {
+    fn from((kind, value): (MessageSetKind, MessageSetVersionXml)) -> Self {
+        let core = value.msgsetcore;
+        MessageSet {
+            kind,
+            ver: core.ver,
+            url: core.url,
+            ofxsec: core.ofxsec,
+            transpsec: OfxParser::parse_yes_no(&core.transpsec),
+            signonrealm: core.signonrealm,
+            language: core.language,
+            syncmode: core.syncmode,
+            respfileer: OfxParser::parse_yes_no(&core.respfileer),
+            spname: core.spname,
+            closingavail: value
+                .closingavail
+                .map(|i: String| OfxParser::parse_yes_no(&i)),
+            unknown: Vec::new(),
+        }
+    }
+}
+
+impl From<SignonInfoXml> for SignonInfo {
+    fn from(value: SignonInfoXml) -> Self {
+        SignonInfo {
+            signonrealm: value.signonrealm,
+            min: value.min,
+            max: value.max,
+            chartype: match value.chartype.trim() {
+                "ALPHAONLY" => CharType::AlphaOnly,
+                "NUMERICONLY" => CharType::NumericOnly,
+                "ALPHAANDNUMERIC" => CharType::AlphaAndNumeric,
+                "ALPHAORNUMERIC" => CharType::AlphaOrNumeric,
+                other => {
+                    warn!("Unknown CHARTYPE {other}. Assuming ALPHAORNUMERIC.");
+                    CharType::AlphaOrNumeric
+                }
+            },
+            casesen: OfxParser::parse_yes_no(&value.casesen),
+            special: OfxParser::parse_yes_no(&value.special),
+            spaces: OfxParser::parse_yes_no(&value.spaces),
+            pinch: OfxParser::parse_yes_no(&value.pinch),
+            chgpinfirst: value
+                .chgpinfirst
+                .is_some_and(|i: String| OfxParser::parse_yes_no(&i)),
+            unknown: Vec::new(),
         }
     }
 }
@@ -380,4 +1089,403 @@ impl From<&TransactionXml> for Transaction {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    const HEADER: &str = "OFXHEADER:100\nDATA:OFXSGML\nVERSION:102\nSECURITY:NONE\nENCODING:USASCII\nCHARSET:1252\nCOMPRESSION:NONE\nOLDFILEUID:NONE\nNEWFILEUID:NONE\n\n";
+
+    const SIGNON: &str = "<SIGNONMSGSRSV1><SONRS><STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS><DTSERVER>20250604120000[-3:BRT]</DTSERVER><LANGUAGE>POR</LANGUAGE></SONRS></SIGNONMSGSRSV1>";
+
+    fn msgset(wrapper: &str, realm: &str) -> String {
+        format!(
+            "<{wrapper}><{wrapper}V1><MSGSETCORE><VER>1</VER><URL>https://ofx.example.com</URL><OFXSEC>NONE</OFXSEC><TRANSPSEC>Y</TRANSPSEC><SIGNONREALM>{realm}</SIGNONREALM><LANGUAGE>ENG</LANGUAGE><SYNCMODE>LITE</SYNCMODE><RESPFILEER>N</RESPFILEER></MSGSETCORE></{wrapper}V1></{wrapper}>"
+        )
+    }
+
+    fn profile_document() -> String {
+        format!(
+            "{HEADER}<OFX>{SIGNON}<PROFMSGSRSV1><PROFTRNRS><TRNUID>1</TRNUID><STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS><PROFRS><MSGSETLIST>{}{}{}</MSGSETLIST><SIGNONINFOLIST><SIGNONINFO><SIGNONREALM>PINS</SIGNONREALM><MIN>4</MIN><MAX>6</MAX><CHARTYPE>NUMERICONLY</CHARTYPE><CASESEN>N</CASESEN><SPECIAL>N</SPECIAL><SPACES>N</SPACES><PINCH>Y</PINCH></SIGNONINFO></SIGNONINFOLIST><DTPROFUP>20250101000000[0:GMT]</DTPROFUP><FINAME>Example Bank</FINAME><ADDR1>1 Main St</ADDR1><CITY>Springfield</CITY><STATE>SP</STATE><POSTALCODE>01000</POSTALCODE></PROFRS></PROFTRNRS></PROFMSGSRSV1></OFX>",
+            msgset("SIGNONMSGSET", "PINS"),
+            msgset("BANKMSGSET", "PINS"),
+            msgset("PROFMSGSET", "PINS"),
+        )
+    }
+
+    fn transaction(fitid: &str, amount: &str) -> String {
+        format!(
+            "<STMTTRN><TRNTYPE>DEBIT</TRNTYPE><DTPOSTED>20250602000000[-3:BRT]</DTPOSTED><TRNAMT>{amount}</TRNAMT><FITID>{fitid}</FITID><NAME>Store {fitid}</NAME></STMTTRN>"
+        )
+    }
+
+    fn statement_document(signon: &str, stmtrs_extra: &str, tranlist: &str) -> String {
+        format!(
+            "{HEADER}<OFX>{signon}<BANKMSGSRSV1><STMTTRNRS><TRNUID>1</TRNUID><STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS><STMTRS><CURDEF>BRL</CURDEF><BANKACCTFROM><BANKID>0001</BANKID><ACCTID>12345</ACCTID><ACCTTYPE>CHECKING</ACCTTYPE></BANKACCTFROM><BANKTRANLIST><DTSTART>20250601000000[-3:BRT]</DTSTART><DTEND>20250630000000[-3:BRT]</DTEND>{tranlist}</BANKTRANLIST><LEDGERBAL><BALAMT>100.00</BALAMT><DTASOF>20250630000000[-3:BRT]</DTASOF></LEDGERBAL>{stmtrs_extra}</STMTRS></STMTTRNRS></BANKMSGSRSV1></OFX>"
+        )
+    }
+
+    #[test]
+    fn parses_statement_without_transactions() {
+        let document = OfxParser::parse_string(&statement_document(SIGNON, "", "")).unwrap();
+
+        let list = document.body.bank_msgs[0]
+            .stmtrs
+            .banktranlist
+            .as_ref()
+            .unwrap();
+        assert!(list.transactions.is_empty());
+    }
+
+    #[test]
+    fn parses_profile_response_without_bank_messages() {
+        let document = OfxParser::parse_string(&profile_document()).unwrap();
+
+        assert!(document.body.bank_msgs.is_empty());
+        let profile = document.body.profile().unwrap();
+        assert_eq!(profile.finame, "Example Bank");
+        assert_eq!(profile.address, vec!["1 Main St".to_string()]);
+        assert_eq!(profile.message_sets.len(), 3);
+        assert_eq!(profile.signon_infos[0].chartype, CharType::NumericOnly);
+    }
+
+    #[test]
+    fn reads_every_message_set_wrapper() {
+        let document = profile_document();
+        let (start, end) = (
+            document.find("<MSGSETLIST>").unwrap() + "<MSGSETLIST>".len(),
+            document.find("</MSGSETLIST>").unwrap(),
+        );
+        let every_set: String = MESSAGE_SET_TAGS
+            .iter()
+            .map(|(_, tag)| msgset(tag, "PINS"))
+            .collect();
+        let document = format!("{}{every_set}{}", &document[..start], &document[end..]);
+
+        let document = OfxParser::parse_string(&document).unwrap();
+
+        let tags: Vec<&str> = MESSAGE_SET_TAGS.iter().map(|(_, tag)| *tag).collect();
+        assert_eq!(tags, MessageSetListXml::TAGS);
+        assert_eq!(
+            document
+                .body
+                .profile()
+                .unwrap()
+                .message_sets
+                .iter()
+                .map(|m| m.kind)
+                .collect::<Vec<_>>(),
+            MESSAGE_SET_TAGS.map(|(kind, _)| kind)
+        );
+    }
+
+    #[test]
+    fn answers_message_set_and_password_questions() {
+        let document = OfxParser::parse_string(&profile_document()).unwrap();
+        let profile = document.body.profile().unwrap();
+
+        assert!(profile.supports_bank_download());
+        assert!(!profile.supports_credit_card_download());
+
+        let rules = profile.password_constraints(MessageSetKind::Bank).unwrap();
+        assert_eq!((rules.min, rules.max), (4, 6));
+        assert!(rules.pinch);
+        assert!(rules.accepts("1234"));
+        assert!(!rules.accepts("12a4"));
+        assert!(!rules.accepts("1234567"));
+    }
+
+    #[test]
+    fn preserves_quicken_extensions() {
+        let signon = SIGNON.replace(
+            "<LANGUAGE>POR</LANGUAGE>",
+            "<LANGUAGE>POR</LANGUAGE><INTU.BID>3000</INTU.BID><INTU.USERID>jdoe</INTU.USERID>",
+        );
+        let tranlist = format!(
+            "{}<INTU.MARKER>between</INTU.MARKER>{}",
+            transaction("1", "-10.00"),
+            transaction("2", "-5.50")
+        );
+        let content = statement_document(
+            &signon,
+            "<INTU.XFER><INTU.DTXFER>20250601</INTU.DTXFER></INTU.XFER><INTU.CLIENT>QW</INTU.CLIENT>",
+            &tranlist,
+        );
+
+        let document = OfxParser::parse_string(&content).unwrap();
+
+        let sonrs = &document.body.sign_on_response.extensions;
+        assert_eq!(sonrs.bid.as_deref(), Some("3000"));
+        assert_eq!(sonrs.get("INTU.USERID"), Some("jdoe"));
+
+        let stmtrs = &document.body.bank_msgs[0].stmtrs;
+        assert_eq!(stmtrs.extensions.get("INTU.CLIENT"), Some("QW"));
+        assert_eq!(
+            stmtrs.extensions.get("INTU.XFER/INTU.DTXFER"),
+            Some("20250601")
+        );
+        assert_eq!(stmtrs.banktranlist.as_ref().unwrap().transactions.len(), 2);
+    }
+
+    #[test]
+    fn keeps_unknown_elements_on_their_aggregates() {
+        let tranlist = transaction("1", "-10.00").replace(
+            "</STMTTRN>",
+            "<SIC>5411</SIC><PAYEE><NAME>Store</NAME><CITY>Recife</CITY></PAYEE></STMTTRN>",
+        );
+        let content = statement_document(SIGNON, "<MKTGINFO>Hello</MKTGINFO>", &tranlist)
+            .replace(
+                "</OFX>",
+                "<CREDITCARDMSGSRSV1><X>1</X></CREDITCARDMSGSRSV1></OFX>",
+            )
+            .replace(
+                "</STMTTRNRS></BANKMSGSRSV1>",
+                "</STMTTRNRS><STMTENDTRNRS><TRNUID>2</TRNUID></STMTENDTRNRS></BANKMSGSRSV1>",
+            );
+
+        let document = OfxParser::parse_string(&content).unwrap();
+
+        assert_eq!(document.body.unknown.len(), 1);
+        assert_eq!(document.body.unknown[0].tag, "CREDITCARDMSGSRSV1");
+        assert_eq!(document.body.unknown[0].position, 2);
+
+        let bank = &document.body.message_set_unknown["BANKMSGSRSV1"];
+        assert_eq!(
+            bank.iter()
+                .map(|u| (u.parent.as_str(), u.position, u.tag.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("BANKMSGSRSV1", 1, "STMTENDTRNRS")]
+        );
+        assert_eq!(document.body.message_set_unknown.len(), 1);
+
+        let statement = &document.body.bank_msgs[0].stmtrs;
+        assert_eq!(statement.unknown[0].tag, "MKTGINFO");
+        assert_eq!(statement.unknown[0].value.as_deref(), Some("Hello"));
+
+        let unknown = &statement.banktranlist.as_ref().unwrap().transactions[0].unknown;
+        assert_eq!(
+            unknown.iter().map(|u| u.tag.as_str()).collect::<Vec<_>>(),
+            vec!["SIC", "PAYEE"]
+        );
+        assert_eq!(unknown[1].parent, "STMTTRN");
+        assert_eq!(unknown[1].children[1].value.as_deref(), Some("Recife"));
+    }
+
+    #[test]
+    fn error_columns_count_characters() {
+        let column = |name: &str| {
+            let content = statement_document(SIGNON, "", &transaction("1", "-10.00"))
+                .replace("Store 1</NAME>", &format!("{name}</NAMEX>"));
+            OfxParser::parse_string(&content)
+                .unwrap_err()
+                .location()
+                .map(|(_, column)| column)
+        };
+
+        assert!(column("Sao Paulo").is_some());
+        assert_eq!(column("São Paulo"), column("Sao Paulo"));
+    }
+
+    #[test]
+    fn lenient_mode_closes_sgml_elements_that_strict_mode_rejects() {
+        let content = statement_document(SIGNON, "", &transaction("1", "-10.00"))
+            .replace("VERSION:102", "VERSION:103")
+            .replace("</TRNAMT>", "\n")
+            .replace("</FITID>", "");
+
+        let error = OfxParser::parse_string(&content).unwrap_err();
+        assert!(matches!(error, OfxError::InvalidVersion(_)));
+
+        let strict_body = content.replace("VERSION:103", "VERSION:102");
+        let error = OfxParser::parse_string(&strict_body).unwrap_err();
+        assert_eq!(error.location().map(|(line, _)| line), Some(12));
+
+        let options = ParseOptions {
+            lenient: true,
+            ..Default::default()
+        };
+        let (document, warnings) =
+            OfxParser::parse_string_with_warnings(&content, &options).unwrap();
+        assert_eq!(document.source.unwrap().to_string(), content);
+
+        let transaction = &document.body.bank_msgs[0]
+            .stmtrs
+            .banktranlist
+            .as_ref()
+            .unwrap()
+            .transactions[0];
+        assert_eq!(
+            (transaction.trnamt, transaction.fitid.as_str()),
+            (-10.0, "1")
+        );
+        assert_eq!(
+            warnings.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "line 3: Invalid OFX version: 103".to_string(),
+                "line 11: Closed 2 elements that had no end tag, as in SGML".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_the_file_as_written() {
+        let content = statement_document(
+            SIGNON,
+            "<MKTGINFO lang=\"en\"> Hello &amp; welcome </MKTGINFO>",
+            &transaction("1", "-10.00"),
+        )
+        .replace("<BANKTRANLIST>", "<BANKTRANLIST>\n  <!-- June -->\n  ");
+
+        let document = OfxParser::parse_string(&content).unwrap();
+
+        let source = document.source.as_ref().unwrap();
+        assert_eq!(source.to_string(), content);
+        let trnamt = source
+            .find("OFX/BANKMSGSRSV1/STMTTRNRS/STMTRS/BANKTRANLIST/STMTTRN/TRNAMT")
+            .unwrap();
+        assert_eq!(trnamt.text, vec!["-10.00".to_string()]);
+
+        let unknown = &document.body.bank_msgs[0].stmtrs.unknown[0];
+        assert_eq!(unknown.value.as_deref(), Some("Hello & welcome"));
+        assert_eq!(unknown.text.as_deref(), Some(" Hello &amp; welcome "));
+        assert_eq!(
+            unknown.attributes,
+            vec![("lang".to_string(), "en".to_string())]
+        );
+    }
+
+    #[test]
+    fn parses_transaction_currencies() {
+        let tranlist = transaction("1", "-52.10").replace(
+            "</STMTTRN>",
+            "<ORIGCURRENCY><CURRATE>5.21</CURRATE><CURSYM>usd</CURSYM><RATESRC>ECB</RATESRC></ORIGCURRENCY></STMTTRN>",
+        );
+        let content = statement_document(SIGNON, "", &tranlist);
+
+        let document = OfxParser::parse_string(&content).unwrap();
+        let transaction = &document.body.bank_msgs[0]
+            .stmtrs
+            .banktranlist
+            .as_ref()
+            .unwrap()
+            .transactions[0];
+
+        assert_eq!(
+            transaction.origcurrency,
+            Some(Currency {
+                currate: 5.21,
+                cursym: "USD".to_string(),
+                unknown: vec![UnknownElement {
+                    parent: "ORIGCURRENCY".to_string(),
+                    position: 2,
+                    tag: "RATESRC".to_string(),
+                    value: Some("ECB".to_string()),
+                    text: Some("ECB".to_string()),
+                    attributes: Vec::new(),
+                    children: Vec::new(),
+                }],
+            })
+        );
+        assert_eq!(transaction.currency, None);
+        assert!(transaction.unknown.is_empty());
+    }
+
+    #[test]
+    fn applies_transaction_corrections() {
+        let correction = |fitid: &str, target: &str, action: &str, amount: &str| {
+            transaction(fitid, amount).replace(
+                "<NAME>",
+                &format!(
+                    "<CORRECTFITID>{target}</CORRECTFITID><CORRECTACTION>{action}</CORRECTACTION><NAME>"
+                ),
+            )
+        };
+        let tranlist = [
+            transaction("1", "-10.00"),
+            transaction("2", "-20.00"),
+            transaction("3", "-30.00"),
+            correction("4", "1", "REPLACE", "-11.00"),
+            correction("5", "2", "DELETE", "0"),
+        ]
+        .concat();
+        let content = statement_document(SIGNON, "", &tranlist);
+
+        let document = OfxParser::parse_string(&content).unwrap();
+        let list = document.body.bank_msgs[0]
+            .stmtrs
+            .banktranlist
+            .as_ref()
+            .unwrap();
+
+        assert_eq!(
+            list.transactions[3].correctaction,
+            Some(CorrectAction::Replace)
+        );
+        assert_eq!(list.transactions[4].correctfitid.as_deref(), Some("2"));
+        assert_eq!(
+            list.effective_transactions()
+                .iter()
+                .map(|t| (t.fitid.as_str(), t.trnamt))
+                .collect::<Vec<_>>(),
+            vec![("3", -30.0), ("4", -11.0)]
+        );
+    }
+
+    #[rstest]
+    #[case("20250604000000[-3:BRT]", "2025-06-04T00:00:00-03:00", Some("BRT"))]
+    #[case("20180115120000[-3:BRT]", "2018-01-15T12:00:00-03:00", Some("BRT"))]
+    #[case("20180115120000[MSK]", "2018-01-15T12:00:00+03:00", Some("MSK"))]
+    #[case("20250604120000[JST]", "2025-06-04T12:00:00+09:00", Some("JST"))]
+    #[case("20250604120000[HST]", "2025-06-04T12:00:00-10:00", Some("HST"))]
+    #[case("20250604120000[AMT]", "2025-06-04T12:00:00-04:00", Some("AMT"))]
+    #[case("20250604120000[IST]", "2025-06-04T12:00:00+05:30", Some("IST"))]
+    #[case("20250604120000[+1:IST]", "2025-06-04T12:00:00+01:00", Some("IST"))]
+    #[case("20250604000000[EST]", "2025-06-04T00:00:00-05:00", Some("EST"))]
+    #[case("20250604000000[0:GMT]", "2025-06-04T00:00:00+00:00", Some("GMT"))]
+    #[case(
+        "20250704120000[-5:America/New_York]",
+        "2025-07-04T12:00:00-04:00",
+        Some("America/New_York")
+    )]
+    #[case(
+        "20250604120000.250[+5.5:XYZ]",
+        "2025-06-04T12:00:00.250+05:30",
+        Some("XYZ")
+    )]
+    #[case("20250604000000[-7]", "2025-06-04T00:00:00-07:00", None)]
+    #[case("20250604120000.5[-3]", "2025-06-04T12:00:00.500-03:00", None)]
+    #[case("20250604", "2025-06-04T00:00:00+00:00", None)]
+    fn resolves_datetime_brackets(
+        #[case] input: &str,
+        #[case] expected: &str,
+        #[case] tz_name: Option<&str>,
+    ) {
+        let parsed = OfxParser::parse_custom_datetime(input).unwrap();
+
+        assert_eq!(parsed.datetime.to_rfc3339(), expected);
+        assert_eq!(parsed.tz_name.as_deref(), tz_name);
+    }
+
+    #[test]
+    fn normalizes_dates_into_the_requested_zone() {
+        let content = statement_document(SIGNON, "", &transaction("1", "-10.00"));
+        let options = ParseOptions {
+            timezone: Some(chrono_tz::Europe::Lisbon),
+            ..Default::default()
+        };
+
+        let document = OfxParser::parse_string_with_options(&content, &options).unwrap();
+        let list = document.body.bank_msgs[0]
+            .stmtrs
+            .banktranlist
+            .as_ref()
+            .unwrap();
+
+        assert_eq!(
+            list.transactions[0].dtposted.datetime.to_rfc3339(),
+            "2025-06-02T04:00:00+01:00"
+        );
+        assert_eq!(
+            list.transactions[0].dtposted.tz_name.as_deref(),
+            Some("BRT")
+        );
+    }
 }
@@ -0,0 +1,251 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::domain::services::reports::{Report, Totals};
+
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Unsupported export format: {0}, expected .csv or .json")]
+    UnsupportedFormat(String),
+}
+
+pub type ExportResult<T> = Result<T, ExportError>;
+
+#[derive(Serialize)]
+struct ReportJson<'a> {
+    period: String,
+    group_by: String,
+    average_income: f64,
+    average_expense: f64,
+    average_net: f64,
+    periods: Vec<PeriodJson<'a>>,
+    trends: Vec<TrendJson<'a>>,
+}
+
+#[derive(Serialize)]
+struct PeriodJson<'a> {
+    period: &'a str,
+    start: String,
+    #[serde(flatten)]
+    totals: TotalsJson,
+    groups: Vec<GroupJson<'a>>,
+    transfers: TotalsJson,
+}
+
+#[derive(Serialize)]
+struct GroupJson<'a> {
+    group: &'a str,
+    #[serde(flatten)]
+    totals: TotalsJson,
+}
+
+#[derive(Serialize)]
+struct TotalsJson {
+    income: f64,
+    expense: f64,
+    net: f64,
+    count: usize,
+}
+
+#[derive(Serialize)]
+struct TrendJson<'a> {
+    group: &'a str,
+    average: f64,
+    latest: f64,
+    change: Option<f64>,
+}
+
+impl From<&Totals> for TotalsJson {
+    fn from(value: &Totals) -> Self {
+        TotalsJson {
+            income: cents(value.income),
+            expense: cents(value.expense),
+            net: cents(value.net()),
+            count: value.count,
+        }
+    }
+}
+
+impl<'a> From<&'a Report> for ReportJson<'a> {
+    fn from(value: &'a Report) -> Self {
+        let (average_income, average_expense, average_net) = value.averages();
+        ReportJson {
+            period: value.period.to_string(),
+            group_by: value.group_by.to_string(),
+            average_income: cents(average_income),
+            average_expense: cents(average_expense),
+            average_net: cents(average_net),
+            periods: value
+                .periods
+                .iter()
+                .map(|p| PeriodJson {
+                    period: &p.label,
+                    start: p.start.to_string(),
+                    totals: (&p.totals).into(),
+                    groups: p
+                        .groups
+                        .iter()
+                        .map(|(group, totals)| GroupJson {
+                            group,
+                            totals: totals.into(),
+                        })
+                        .collect(),
+                    transfers: (&p.transfers).into(),
+                })
+                .collect(),
+            trends: value
+                .trends
+                .iter()
+                .map(|t| TrendJson {
+                    group: &t.group,
+                    average: cents(t.average),
+                    latest: cents(t.latest),
+                    change: t.change,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Writes the report as CSV or JSON, depending on the file extension.
+pub fn export_report(report: &Report, path: &Path) -> ExportResult<()> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let writer = || File::create(path).map(BufWriter::new);
+
+    match extension.as_str() {
+        "csv" => write_csv(report, writer()?),
+        "json" => write_json(report, writer()?),
+        _ => Err(ExportError::UnsupportedFormat(path.display().to_string())),
+    }
+}
+
+pub fn write_json(report: &Report, writer: impl Write) -> ExportResult<()> {
+    serde_json::to_writer_pretty(writer, &ReportJson::from(report))?;
+    Ok(())
+}
+
+/// One row per period and group, preceded by the period total with an empty group, and an
+/// `average` row per period at the end. The group trends follow as a second table after an empty
+/// line.
+pub fn write_csv(report: &Report, mut writer: impl Write) -> ExportResult<()> {
+    writeln!(writer, "period,group,income,expense,net,count")?;
+    for period in &report.periods {
+        let rows = std::iter::once(("", &period.totals))
+            .chain(period.groups.iter().map(|(g, t)| (g.as_str(), t)));
+        for (group, totals) in rows {
+            writeln!(
+                writer,
+                "{},{},{:.2},{:.2},{:.2},{}",
+                csv_field(&period.label),
+                csv_field(group),
+                totals.income,
+                totals.expense,
+                totals.net(),
+                totals.count
+            )?;
+        }
+    }
+    let (income, expense, net) = report.averages();
+    writeln!(writer, "average,,{income:.2},{expense:.2},{net:.2},")?;
+
+    writeln!(writer)?;
+    writeln!(writer, "group,average,latest,change")?;
+    for trend in &report.trends {
+        writeln!(
+            writer,
+            "{},{:.2},{:.2},{}",
+            csv_field(&trend.group),
+            trend.average,
+            trend.latest,
+            trend.change.map(|c| format!("{c:.4}")).unwrap_or_default()
+        )?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Sums of `f64` amounts pick up noise in the last digits.
+fn cents(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{
+            entities::ofx::Transaction,
+            services::reports::{GroupBy, Period, ReportOptions, summarize},
+        },
+        test_support::{self, account},
+    };
+    use pretty_assertions::assert_eq;
+
+    fn transaction(month: u32, trnamt: f64, name: &str) -> Transaction {
+        Transaction {
+            name: Some(name.to_string()),
+            ..test_support::transaction(&format!("{month}{name}"), (2025, month, 10), trnamt)
+        }
+    }
+
+    #[test]
+    fn writes_quarterly_csv_by_payee() {
+        let account = account("12345");
+        let transactions = [
+            transaction(1, 1000.0, "SMITH, JOHN"),
+            transaction(2, -40.0, "BAKERY"),
+            transaction(4, -60.0, "BAKERY"),
+        ];
+        let report = summarize(
+            transactions.iter().map(|t| (&account, t)),
+            &ReportOptions {
+                period: Period::Quarter,
+                group_by: GroupBy::Payee,
+                ..Default::default()
+            },
+        );
+
+        let mut csv = Vec::new();
+        write_csv(&report, &mut csv).unwrap();
+
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "period,group,income,expense,net,count\n\
+             2025-Q1,,1000.00,40.00,960.00,2\n\
+             2025-Q1,Bakery,0.00,40.00,-40.00,1\n\
+             2025-Q1,\"Smith, John\",1000.00,0.00,1000.00,1\n\
+             2025-Q2,,0.00,60.00,-60.00,1\n\
+             2025-Q2,Bakery,0.00,60.00,-60.00,1\n\
+             average,,500.00,50.00,450.00,\n\
+             \n\
+             group,average,latest,change\n\
+             Bakery,-50.00,-60.00,-0.5000\n\
+             \"Smith, John\",500.00,0.00,-1.0000\n"
+        );
+        assert_eq!(report.trends[0].change, Some(-0.5));
+    }
+}
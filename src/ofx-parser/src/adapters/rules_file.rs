@@ -0,0 +1,257 @@
+use std::{fs, path::Path};
+
+use chrono::NaiveDate;
+use regex::RegexBuilder;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::domain::services::{
+    categorization::{Categorizer, Rule, RuleConditions, TextMatch},
+    payee::{PayeeAlias, PayeeNormalizer},
+};
+
+#[derive(Error, Debug)]
+pub enum RulesError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid rules file: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    #[error("{entry}: invalid regex: {source}")]
+    InvalidRegex { entry: String, source: regex::Error },
+
+    #[error("{entry}: invalid date {value}, expected YYYY-MM-DD")]
+    InvalidDate { entry: String, value: String },
+}
+
+pub type RulesResult<T> = Result<T, RulesError>;
+
+/// A TOML rules file looks like:
+///
+/// ```toml
+/// [[rule]]
+/// name = "Ride sharing"
+/// category = "Transport"
+/// tags = ["travel"]
+/// when.name = { regex = "^UBER" }
+/// when.amount = { max = 0.0 }
+///
+/// [[alias]]
+/// payee = "Landlord"
+/// pattern = { contains = "JoseDaSilva" }
+/// ```
+#[derive(Debug, Deserialize)]
+struct RulesFileToml {
+    #[serde(default)]
+    rule: Vec<RuleToml>,
+    #[serde(default)]
+    alias: Vec<AliasToml>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleToml {
+    name: String,
+    category: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    when: ConditionsToml,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AliasToml {
+    payee: String,
+    pattern: TextMatchToml,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConditionsToml {
+    name: Option<TextMatchToml>,
+    memo: Option<TextMatchToml>,
+    trntype: Option<TextMatchToml>,
+    account: Option<TextMatchToml>,
+    amount: Option<AmountRangeToml>,
+    from: Option<String>,
+    to: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum TextMatchToml {
+    Contains(String),
+    /// Case-insensitive, like `contains`.
+    Regex(String),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AmountRangeToml {
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+pub fn load_rules(path: &Path) -> RulesResult<Categorizer> {
+    parse_rules(&fs::read_to_string(path)?)
+}
+
+pub fn parse_rules(content: &str) -> RulesResult<Categorizer> {
+    let file: RulesFileToml = toml::from_str(content)?;
+    let rules = file
+        .rule
+        .into_iter()
+        .map(Rule::try_from)
+        .collect::<RulesResult<Vec<_>>>()?;
+    Ok(Categorizer::new(rules))
+}
+
+pub fn load_payee_aliases(path: &Path) -> RulesResult<PayeeNormalizer> {
+    parse_payee_aliases(&fs::read_to_string(path)?)
+}
+
+/// Reads the `[[alias]]` entries of a rules file into a normalizer with the default prefixes.
+pub fn parse_payee_aliases(content: &str) -> RulesResult<PayeeNormalizer> {
+    let file: RulesFileToml = toml::from_str(content)?;
+    let aliases = file
+        .alias
+        .into_iter()
+        .map(|a| {
+            Ok(PayeeAlias {
+                pattern: text_match(&a.payee, a.pattern)?,
+                payee: a.payee,
+            })
+        })
+        .collect::<RulesResult<Vec<_>>>()?;
+    Ok(PayeeNormalizer::with_aliases(aliases))
+}
+
+impl TryFrom<RuleToml> for Rule {
+    type Error = RulesError;
+
+    fn try_from(value: RuleToml) -> RulesResult<Self> {
+        let rule = value.name.as_str();
+        let when = value.when;
+        let amount = when.amount.unwrap_or_default();
+
+        let conditions = RuleConditions {
+            name: when.name.map(|m| text_match(rule, m)).transpose()?,
+            memo: when.memo.map(|m| text_match(rule, m)).transpose()?,
+            trntype: when.trntype.map(|m| text_match(rule, m)).transpose()?,
+            account: when.account.map(|m| text_match(rule, m)).transpose()?,
+            min_amount: amount.min,
+            max_amount: amount.max,
+            from: when.from.map(|d| date(rule, d)).transpose()?,
+            to: when.to.map(|d| date(rule, d)).transpose()?,
+        };
+
+        Ok(Rule {
+            name: value.name,
+            conditions,
+            category: value.category,
+            tags: value.tags,
+        })
+    }
+}
+
+fn text_match(entry: &str, value: TextMatchToml) -> RulesResult<TextMatch> {
+    match value {
+        TextMatchToml::Contains(needle) => Ok(TextMatch::Contains(needle)),
+        TextMatchToml::Regex(pattern) => RegexBuilder::new(&pattern)
+            .case_insensitive(true)
+            .build()
+            .map(TextMatch::Regex)
+            .map_err(|source| RulesError::InvalidRegex {
+                entry: entry.to_string(),
+                source,
+            }),
+    }
+}
+
+fn date(entry: &str, value: String) -> RulesResult<NaiveDate> {
+    NaiveDate::parse_from_str(&value, "%Y-%m-%d").map_err(|_| RulesError::InvalidDate {
+        entry: entry.to_string(),
+        value,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::entities::ofx::Transaction,
+        test_support::{self, account},
+    };
+    use pretty_assertions::assert_eq;
+
+    const RULES: &str = r#"
+[[rule]]
+name = "Ride sharing"
+category = "Transport"
+tags = ["travel"]
+when.name = { regex = "^uber" }
+when.amount = { max = 0 }
+
+[[rule]]
+name = "Savings card purchases"
+category = "Shopping"
+when.trntype = { contains = "pos" }
+when.account = { contains = "999" }
+when.from = "2025-06-01"
+"#;
+
+    fn transaction(trntype: &str, name: &str, trnamt: f64) -> Transaction {
+        Transaction {
+            trntype: trntype.to_string(),
+            name: Some(name.to_string()),
+            ..test_support::transaction("1", (2025, 6, 2), trnamt)
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let categorizer = parse_rules(RULES).unwrap();
+        let account = account("99999");
+
+        let ride = categorizer
+            .categorize(&transaction("POS", "UBER *TRIP", -20.0), Some(&account))
+            .unwrap();
+        assert_eq!(ride.category, "Transport");
+        assert_eq!(ride.tags, vec!["travel".to_string()]);
+        assert_eq!(ride.rule, "Ride sharing");
+
+        let refund = categorizer
+            .categorize(&transaction("POS", "UBER *TRIP", 20.0), Some(&account))
+            .unwrap();
+        assert_eq!(refund.rule, "Savings card purchases");
+
+        assert!(
+            categorizer
+                .categorize(&transaction("POS", "Bakery", -5.0), None)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_regex() {
+        let error = parse_rules(
+            "[[rule]]\nname = \"Broken\"\ncategory = \"X\"\nwhen.name = { regex = \"(\" }\n",
+        )
+        .unwrap_err();
+
+        assert!(matches!(error, RulesError::InvalidRegex { entry, .. } if entry == "Broken"));
+    }
+
+    #[test]
+    fn aliases_override_normalized_payee() {
+        let normalizer = parse_payee_aliases(
+            "[[alias]]\npayee = \"Landlord\"\npattern = { contains = \"jose da silva\" }\n",
+        )
+        .unwrap();
+
+        assert_eq!(normalizer.display_name("PAG*JoseDaSilva 123"), "Landlord");
+        assert_eq!(normalizer.display_name("UBER *TRIP 8XZ1"), "Uber");
+    }
+}
@@ -0,0 +1,192 @@
+use thiserror::Error;
+
+use crate::domain::entities::annotation::{Annotation, Split, SplitMismatch};
+
+#[derive(Error, Debug, PartialEq)]
+pub enum SplitError {
+    #[error("{0}: invalid amount in split")]
+    InvalidAmount(String),
+
+    #[error("{0}: the memo has to be in double quotes at the end of the split")]
+    InvalidMemo(String),
+
+    #[error("only one split can leave out its amount")]
+    SeveralWithoutAmount,
+
+    #[error(transparent)]
+    Mismatch(#[from] SplitMismatch),
+}
+
+pub type SplitResult<T> = Result<T, SplitError>;
+
+/// Reads splits typed as `category=amount` pairs separated by semicolons, each with an optional
+/// memo in double quotes, where `""` stands for a quote:
+///
+/// ```text
+/// Groceries=-60 "weekly shop"; Household
+/// ```
+///
+/// The one split without an amount takes whatever is left of `trnamt`. The splits have to add up
+/// to `trnamt`, and an empty input means no splits.
+pub fn parse_splits(input: &str, trnamt: f64) -> SplitResult<Vec<Split>> {
+    let mut splits = Vec::new();
+    let mut rest: Option<usize> = None;
+
+    for part in split_parts(input).map(str::trim).filter(|p| !p.is_empty()) {
+        let (split, memo) = match part.find('"') {
+            Some(quote) => (part[..quote].trim(), Some(memo(part, &part[quote..])?)),
+            None => (part, None),
+        };
+        let (category, amount) = match split.split_once('=') {
+            Some((category, amount)) => {
+                let amount = amount
+                    .trim()
+                    .parse::<f64>()
+                    .map_err(|_| SplitError::InvalidAmount(part.to_string()))?;
+                (category.trim(), amount)
+            }
+            None if rest.is_none() => {
+                rest = Some(splits.len());
+                (split, 0.0)
+            }
+            None => return Err(SplitError::SeveralWithoutAmount),
+        };
+        splits.push(Split {
+            category: category.to_string(),
+            amount,
+            memo,
+        });
+    }
+
+    if let Some(index) = rest {
+        let assigned: f64 = splits.iter().map(|s| s.amount).sum();
+        splits[index].amount = trnamt - assigned;
+    }
+
+    let annotation = Annotation {
+        splits,
+        ..Default::default()
+    };
+    annotation.check_splits(trnamt)?;
+    Ok(annotation.splits)
+}
+
+/// Writes splits the way [`parse_splits`] reads them.
+pub fn format_splits(splits: &[Split]) -> String {
+    splits
+        .iter()
+        .map(|s| match &s.memo {
+            Some(memo) => format!(
+                "{}={:.2} \"{}\"",
+                s.category,
+                s.amount,
+                memo.replace('"', "\"\"")
+            ),
+            None => format!("{}={:.2}", s.category, s.amount),
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Splits at the semicolons outside of memos.
+fn split_parts(input: &str) -> impl Iterator<Item = &str> {
+    let mut quoted = false;
+    input.split(move |c| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        c == ';' && !quoted
+    })
+}
+
+/// The memo `quoted` holds, which has to run to the end of `part`.
+fn memo(part: &str, quoted: &str) -> SplitResult<String> {
+    quoted
+        .strip_prefix('"')
+        .and_then(|q| q.strip_suffix('"'))
+        .filter(|memo| !memo.replace("\"\"", "").contains('"'))
+        .map(|memo| memo.replace("\"\"", "\""))
+        .ok_or_else(|| SplitError::InvalidMemo(part.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn split(category: &str, amount: f64) -> Split {
+        Split {
+            category: category.to_string(),
+            amount,
+            memo: None,
+        }
+    }
+
+    #[test]
+    fn fills_in_the_split_without_an_amount() {
+        assert_eq!(
+            parse_splits(" Groceries = -60 ; Household ;", -100.0).unwrap(),
+            vec![split("Groceries", -60.0), split("Household", -40.0)]
+        );
+        assert_eq!(
+            parse_splits("Groceries=-60;Household=-40", -100.0).unwrap(),
+            vec![split("Groceries", -60.0), split("Household", -40.0)]
+        );
+        assert_eq!(parse_splits("  ", -100.0).unwrap(), Vec::new());
+    }
+
+    #[test]
+    fn reads_and_writes_memos() {
+        let splits = vec![
+            Split {
+                memo: Some("weekly; \"big\" shop".to_string()),
+                ..split("Groceries", -60.0)
+            },
+            split("Household", -40.0),
+        ];
+
+        assert_eq!(
+            parse_splits(
+                r#"Groceries = -60 "weekly; ""big"" shop"; Household"#,
+                -100.0
+            )
+            .unwrap(),
+            splits
+        );
+        assert_eq!(
+            format_splits(&splits),
+            r#"Groceries=-60.00 "weekly; ""big"" shop"; Household=-40.00"#
+        );
+        assert_eq!(
+            parse_splits(&format_splits(&splits), -100.0).unwrap(),
+            splits
+        );
+        assert_eq!(
+            parse_splits(r#"Groceries=-60 "weekly" shop; Household"#, -100.0).unwrap_err(),
+            SplitError::InvalidMemo(r#"Groceries=-60 "weekly" shop"#.to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_amounts_and_several_open_splits() {
+        assert_eq!(
+            parse_splits("Groceries=sixty; Household", -100.0).unwrap_err(),
+            SplitError::InvalidAmount("Groceries=sixty".to_string())
+        );
+        assert_eq!(
+            parse_splits("Groceries; Household", -100.0).unwrap_err(),
+            SplitError::SeveralWithoutAmount
+        );
+    }
+
+    #[test]
+    fn rejects_splits_that_do_not_add_up() {
+        assert_eq!(
+            parse_splits("Groceries=-60; Household=-30", -100.0).unwrap_err(),
+            SplitError::Mismatch(SplitMismatch {
+                total: -90.0,
+                trnamt: -100.0,
+            })
+        );
+    }
+}
@@ -0,0 +1,787 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use log::{debug, info};
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use thiserror::Error;
+
+use crate::domain::{
+    entities::ofx::{
+        AccountKey, Balance, CorrectAction, Currency, OfxDateTime, OfxDocument, StatementResponse,
+        Transaction,
+    },
+    services::corrections::apply_corrections,
+};
+
+#[derive(Error, Debug)]
+pub enum StoreError {
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("Invalid stored date: {0}")]
+    InvalidDate(String),
+}
+
+pub type StoreResult<T> = Result<T, StoreError>;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS accounts (
+    id INTEGER PRIMARY KEY,
+    bankid TEXT NOT NULL,
+    acctid TEXT NOT NULL,
+    accttype TEXT NOT NULL,
+    curdef TEXT NOT NULL,
+    UNIQUE (bankid, acctid, accttype)
+);
+
+CREATE TABLE IF NOT EXISTS imports (
+    id INTEGER PRIMARY KEY,
+    source TEXT NOT NULL,
+    imported_at TEXT NOT NULL,
+    dtserver TEXT NOT NULL,
+    added INTEGER NOT NULL DEFAULT 0,
+    skipped INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE IF NOT EXISTS statements (
+    id INTEGER PRIMARY KEY,
+    account_id INTEGER NOT NULL REFERENCES accounts (id),
+    import_id INTEGER NOT NULL REFERENCES imports (id),
+    trnuid TEXT NOT NULL,
+    dtstart TEXT,
+    dtstart_tz TEXT,
+    dtend TEXT,
+    dtend_tz TEXT
+);
+
+CREATE TABLE IF NOT EXISTS balances (
+    id INTEGER PRIMARY KEY,
+    statement_id INTEGER NOT NULL REFERENCES statements (id),
+    kind TEXT NOT NULL CHECK (kind IN ('LEDGER', 'AVAIL')),
+    balamt REAL NOT NULL,
+    dtasof TEXT NOT NULL,
+    dtasof_tz TEXT
+);
+
+CREATE TABLE IF NOT EXISTS transactions (
+    id INTEGER PRIMARY KEY,
+    account_id INTEGER NOT NULL REFERENCES accounts (id),
+    import_id INTEGER NOT NULL REFERENCES imports (id),
+    fitid TEXT NOT NULL,
+    trntype TEXT NOT NULL,
+    dtposted TEXT NOT NULL,
+    dtposted_tz TEXT,
+    trnamt REAL NOT NULL,
+    name TEXT,
+    memo TEXT,
+    correctfitid TEXT,
+    correctaction TEXT,
+    currency TEXT,
+    currate REAL,
+    origcurrency TEXT,
+    origcurrate REAL,
+    UNIQUE (account_id, fitid)
+);
+";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredAccount {
+    pub key: AccountKey,
+    pub curdef: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredTransaction {
+    pub transaction: Transaction,
+    /// The import, and so the file, this transaction first arrived in.
+    pub import_id: i64,
+    pub source: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportRecord {
+    pub id: i64,
+    pub source: String,
+    pub imported_at: DateTime<Utc>,
+    pub dtserver: DateTime<Utc>,
+    /// Transactions that were new to the store.
+    pub added: usize,
+    /// Transactions whose `FITID` was already known and were left untouched.
+    pub skipped: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalanceKind {
+    Ledger,
+    Available,
+}
+
+impl BalanceKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            BalanceKind::Ledger => "LEDGER",
+            BalanceKind::Available => "AVAIL",
+        }
+    }
+}
+
+/// Embedded SQLite database holding everything imported from OFX files so far.
+pub struct SqliteStore {
+    connection: Connection,
+}
+
+impl SqliteStore {
+    pub fn open(path: impl AsRef<Path>) -> StoreResult<Self> {
+        info!("Opening transaction store at {}", path.as_ref().display());
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> StoreResult<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> StoreResult<Self> {
+        connection.execute_batch("PRAGMA foreign_keys = ON;")?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(SqliteStore { connection })
+    }
+
+    /// Stores a parsed document, skipping transactions whose `FITID` the account already has and
+    /// statements, balances included, already imported from the same `source`. `source` is
+    /// recorded in the import history, usually the file path.
+    pub fn import_document(
+        &mut self,
+        document: &OfxDocument,
+        source: &str,
+    ) -> StoreResult<ImportRecord> {
+        let tx = self.connection.transaction()?;
+        let imported_at = Utc::now();
+        let dtserver = document
+            .body
+            .sign_on_response
+            .dtserver
+            .datetime
+            .with_timezone(&Utc);
+
+        tx.execute(
+            "INSERT INTO imports (source, imported_at, dtserver) VALUES (?1, ?2, ?3)",
+            params![source, imported_at.to_rfc3339(), dtserver.to_rfc3339()],
+        )?;
+        let import_id = tx.last_insert_rowid();
+
+        let mut added = 0;
+        let mut skipped = 0;
+        for message in &document.body.bank_msgs {
+            let stmtrs = &message.stmtrs;
+            let account_id = Self::upsert_account(&tx, stmtrs)?;
+            let statement_id =
+                Self::insert_statement(&tx, account_id, import_id, &message.trnuid, stmtrs)?;
+
+            for (kind, balance) in [
+                (BalanceKind::Ledger, &stmtrs.ledgerbal),
+                (BalanceKind::Available, &stmtrs.availbal),
+            ] {
+                if let (Some(statement_id), Some(balance)) = (statement_id, balance) {
+                    tx.execute(
+                        "INSERT INTO balances (statement_id, kind, balamt, dtasof, dtasof_tz)
+                         VALUES (?1, ?2, ?3, ?4, ?5)",
+                        params![
+                            statement_id,
+                            kind.as_str(),
+                            balance.balamt,
+                            balance.dtasof.datetime.to_rfc3339(),
+                            balance.dtasof.tz_name
+                        ],
+                    )?;
+                }
+            }
+
+            for transaction in stmtrs.banktranlist.iter().flat_map(|l| &l.transactions) {
+                let inserted = tx.execute(
+                    "INSERT OR IGNORE INTO transactions (
+                        account_id, import_id, fitid, trntype, dtposted, dtposted_tz, trnamt,
+                        name, memo, correctfitid, correctaction, currency, currate,
+                        origcurrency, origcurrate
+                    ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+                    params![
+                        account_id,
+                        import_id,
+                        transaction.fitid,
+                        transaction.trntype,
+                        transaction.dtposted.datetime.to_rfc3339(),
+                        transaction.dtposted.tz_name,
+                        transaction.trnamt,
+                        transaction.name,
+                        transaction.memo,
+                        transaction.correctfitid,
+                        transaction.correctaction.map(|a| match a {
+                            CorrectAction::Replace => "REPLACE",
+                            CorrectAction::Delete => "DELETE",
+                        }),
+                        transaction.currency.as_ref().map(|c| &c.cursym),
+                        transaction.currency.as_ref().map(|c| c.currate),
+                        transaction.origcurrency.as_ref().map(|c| &c.cursym),
+                        transaction.origcurrency.as_ref().map(|c| c.currate),
+                    ],
+                )?;
+
+                if inserted == 0 {
+                    debug!("Skipping known FITID {}", transaction.fitid);
+                    skipped += 1;
+                } else {
+                    added += 1;
+                }
+            }
+        }
+
+        tx.execute(
+            "UPDATE imports SET added = ?1, skipped = ?2 WHERE id = ?3",
+            params![added, skipped, import_id],
+        )?;
+        tx.commit()?;
+
+        info!("Imported {source}: {added} new transactions, {skipped} already known");
+        Ok(ImportRecord {
+            id: import_id,
+            source: source.to_string(),
+            imported_at,
+            dtserver,
+            added,
+            skipped,
+        })
+    }
+
+    fn upsert_account(connection: &Connection, stmtrs: &StatementResponse) -> StoreResult<i64> {
+        let account = &stmtrs.bankacctfrom;
+        connection.execute(
+            "INSERT INTO accounts (bankid, acctid, accttype, curdef) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (bankid, acctid, accttype) DO UPDATE SET curdef = excluded.curdef",
+            params![
+                account.bankid,
+                account.acctid,
+                account.accttype,
+                stmtrs.curdef
+            ],
+        )?;
+
+        Ok(connection.query_row(
+            "SELECT id FROM accounts WHERE bankid = ?1 AND acctid = ?2 AND accttype = ?3",
+            params![account.bankid, account.acctid, account.accttype],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// Records the statement and returns its id, or `None` when the account already has a
+    /// statement with the same `TRNUID` and period from the same source, as happens when a file
+    /// is imported again.
+    fn insert_statement(
+        connection: &Connection,
+        account_id: i64,
+        import_id: i64,
+        trnuid: &str,
+        stmtrs: &StatementResponse,
+    ) -> StoreResult<Option<i64>> {
+        let list = stmtrs.banktranlist.as_ref();
+        let dtstart = list.map(|l| l.dtstart.datetime.to_rfc3339());
+        let dtend = list.map(|l| l.dtend.datetime.to_rfc3339());
+
+        let known = connection
+            .query_row(
+                "SELECT s.id FROM statements s
+                 JOIN imports i ON i.id = s.import_id
+                 WHERE s.account_id = ?1 AND s.trnuid = ?2 AND s.dtstart IS ?3 AND s.dtend IS ?4
+                   AND i.source = (SELECT source FROM imports WHERE id = ?5)",
+                params![account_id, trnuid, dtstart, dtend, import_id],
+                |row| row.get::<_, i64>(0),
+            )
+            .optional()?;
+        if let Some(id) = known {
+            debug!("Skipping known statement {trnuid}, stored as {id}");
+            return Ok(None);
+        }
+
+        connection.execute(
+            "INSERT INTO statements (
+                account_id, import_id, trnuid, dtstart, dtstart_tz, dtend, dtend_tz
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                account_id,
+                import_id,
+                trnuid,
+                dtstart,
+                list.and_then(|l| l.dtstart.tz_name.clone()),
+                dtend,
+                list.and_then(|l| l.dtend.tz_name.clone()),
+            ],
+        )?;
+        Ok(Some(connection.last_insert_rowid()))
+    }
+
+    pub fn accounts(&self) -> StoreResult<Vec<StoredAccount>> {
+        let mut statement = self.connection.prepare(
+            "SELECT bankid, acctid, accttype, curdef FROM accounts ORDER BY bankid, acctid",
+        )?;
+        let accounts = statement
+            .query_map([], |row| {
+                Ok(StoredAccount {
+                    key: AccountKey {
+                        bankid: row.get(0)?,
+                        acctid: row.get(1)?,
+                        accttype: row.get(2)?,
+                    },
+                    curdef: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(accounts)
+    }
+
+    /// Every stored transaction of the account in posting order, corrections included as-is.
+    pub fn transactions(&self, account: &AccountKey) -> StoreResult<Vec<StoredTransaction>> {
+        let mut statement = self.connection.prepare(
+            "SELECT t.fitid, t.trntype, t.dtposted, t.dtposted_tz, t.trnamt, t.name, t.memo,
+                    t.correctfitid, t.correctaction, t.currency, t.currate, t.origcurrency,
+                    t.origcurrate, i.id, i.source
+             FROM transactions t
+             JOIN accounts a ON a.id = t.account_id
+             JOIN imports i ON i.id = t.import_id
+             WHERE a.bankid = ?1 AND a.acctid = ?2 AND a.accttype = ?3
+             ORDER BY t.id",
+        )?;
+        let rows = statement
+            .query_map(
+                params![account.bankid, account.acctid, account.accttype],
+                |row| Ok((Self::transaction_from_row(row)?, row.get(13)?, row.get(14)?)),
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut stored = rows
+            .into_iter()
+            .map(|(transaction, import_id, source)| {
+                Ok(StoredTransaction {
+                    transaction: transaction?,
+                    import_id,
+                    source,
+                })
+            })
+            .collect::<StoreResult<Vec<_>>>()?;
+
+        // Stored dates keep their original offsets, so SQL text ordering would not be chronological
+        stored.sort_by_key(|s| s.transaction.dtposted.datetime);
+        Ok(stored)
+    }
+
+    /// The account's transactions with every stored correction applied.
+    pub fn effective_transactions(&self, account: &AccountKey) -> StoreResult<Vec<Transaction>> {
+        let stored = self.transactions(account)?;
+        Ok(apply_corrections(stored.iter().map(|s| &s.transaction)))
+    }
+
+    /// The most recent balance of the given kind across all imports.
+    pub fn latest_balance(
+        &self,
+        account: &AccountKey,
+        kind: BalanceKind,
+    ) -> StoreResult<Option<Balance>> {
+        let mut statement = self.connection.prepare(
+            "SELECT b.balamt, b.dtasof, b.dtasof_tz
+             FROM balances b
+             JOIN statements s ON s.id = b.statement_id
+             JOIN accounts a ON a.id = s.account_id
+             WHERE a.bankid = ?1 AND a.acctid = ?2 AND a.accttype = ?3 AND b.kind = ?4
+             ORDER BY b.id",
+        )?;
+        let rows = statement
+            .query_map(
+                params![
+                    account.bankid,
+                    account.acctid,
+                    account.accttype,
+                    kind.as_str()
+                ],
+                |row| {
+                    Ok((
+                        row.get::<_, f64>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Option<String>>(2)?,
+                    ))
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let balances = rows
+            .into_iter()
+            .map(|(balamt, dtasof, tz_name)| {
+                Ok(Balance {
+                    balamt,
+                    dtasof: parse_stored_date(&dtasof, tz_name)?,
+                    unknown: Vec::new(),
+                })
+            })
+            .collect::<StoreResult<Vec<_>>>()?;
+
+        // max_by_key keeps the last of equal elements, so on ties the balance imported last wins
+        Ok(balances.into_iter().max_by_key(|b| b.dtasof.datetime))
+    }
+
+    /// Earliest `DTSTART` and latest `DTEND` across the account's imported statements.
+    pub fn statement_period(
+        &self,
+        account: &AccountKey,
+    ) -> StoreResult<Option<(OfxDateTime, OfxDateTime)>> {
+        let mut statement = self.connection.prepare(
+            "SELECT s.dtstart, s.dtstart_tz, s.dtend, s.dtend_tz
+             FROM statements s
+             JOIN accounts a ON a.id = s.account_id
+             WHERE a.bankid = ?1 AND a.acctid = ?2 AND a.accttype = ?3
+               AND s.dtstart IS NOT NULL AND s.dtend IS NOT NULL",
+        )?;
+        let rows = statement
+            .query_map(
+                params![account.bankid, account.acctid, account.accttype],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, Option<String>>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, Option<String>>(3)?,
+                    ))
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut period: Option<(OfxDateTime, OfxDateTime)> = None;
+        for (dtstart, dtstart_tz, dtend, dtend_tz) in rows {
+            let dtstart = parse_stored_date(&dtstart, dtstart_tz)?;
+            let dtend = parse_stored_date(&dtend, dtend_tz)?;
+            period = Some(match period {
+                Some((start, end)) => (
+                    if dtstart.datetime < start.datetime {
+                        dtstart
+                    } else {
+                        start
+                    },
+                    if dtend.datetime > end.datetime {
+                        dtend
+                    } else {
+                        end
+                    },
+                ),
+                None => (dtstart, dtend),
+            });
+        }
+        Ok(period)
+    }
+
+    pub fn imports(&self) -> StoreResult<Vec<ImportRecord>> {
+        let mut statement = self.connection.prepare(
+            "SELECT id, source, imported_at, dtserver, added, skipped FROM imports ORDER BY id",
+        )?;
+        let rows = statement
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, usize>(4)?,
+                    row.get::<_, usize>(5)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        rows.into_iter()
+            .map(|(id, source, imported_at, dtserver, added, skipped)| {
+                Ok(ImportRecord {
+                    id,
+                    source,
+                    imported_at: parse_stored_date(&imported_at, None)?
+                        .datetime
+                        .with_timezone(&Utc),
+                    dtserver: parse_stored_date(&dtserver, None)?
+                        .datetime
+                        .with_timezone(&Utc),
+                    added,
+                    skipped,
+                })
+            })
+            .collect()
+    }
+
+    fn transaction_from_row(row: &Row) -> rusqlite::Result<StoreResult<Transaction>> {
+        let dtposted: String = row.get(2)?;
+        let tz_name: Option<String> = row.get(3)?;
+        let correctaction: Option<String> = row.get(8)?;
+
+        let fitid: String = row.get(0)?;
+        let trntype: String = row.get(1)?;
+        let trnamt: f64 = row.get(4)?;
+        let name: Option<String> = row.get(5)?;
+        let memo: Option<String> = row.get(6)?;
+        let correctfitid: Option<String> = row.get(7)?;
+        let currency = |cursym: usize, currate: usize| -> rusqlite::Result<Option<Currency>> {
+            let cursym: Option<String> = row.get(cursym)?;
+            let currate: Option<f64> = row.get(currate)?;
+            Ok(cursym.zip(currate).map(|(cursym, currate)| Currency {
+                currate,
+                cursym,
+                unknown: Vec::new(),
+            }))
+        };
+        let (currency, origcurrency) = (currency(9, 10)?, currency(11, 12)?);
+
+        Ok(
+            parse_stored_date(&dtposted, tz_name).map(|dtposted| Transaction {
+                trntype,
+                dtposted,
+                trnamt,
+                fitid,
+                name,
+                memo,
+                correctfitid,
+                correctaction: correctaction.as_deref().and_then(|a| match a {
+                    "REPLACE" => Some(CorrectAction::Replace),
+                    "DELETE" => Some(CorrectAction::Delete),
+                    _ => None,
+                }),
+                currency,
+                origcurrency,
+                unknown: Vec::new(),
+            }),
+        )
+    }
+}
+
+fn parse_stored_date(s: &str, tz_name: Option<String>) -> StoreResult<OfxDateTime> {
+    let datetime = DateTime::parse_from_rfc3339(s)
+        .map_err(|e| StoreError::InvalidDate(format!("{s}: {e}")))?;
+    Ok(OfxDateTime {
+        datetime,
+        tz_name,
+        raw: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::entities::ofx::StatementTransactionResponse,
+        test_support::{account, balance, document, statement, transaction},
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn round_trips_transaction_currencies() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        let converted = Transaction {
+            currency: Some(Currency {
+                currate: 5.5,
+                cursym: "USD".to_string(),
+                unknown: Vec::new(),
+            }),
+            origcurrency: Some(Currency {
+                currate: 6.1,
+                cursym: "EUR".to_string(),
+                unknown: Vec::new(),
+            }),
+            ..transaction("1", (2025, 6, 2), -55.0)
+        };
+        let plain = transaction("2", (2025, 6, 3), -10.0);
+        let doc = document(vec![statement("1", vec![converted.clone(), plain.clone()])]);
+
+        store.import_document(&doc, "a.ofx").unwrap();
+
+        let stored = store.transactions(&account("1")).unwrap();
+        assert_eq!(
+            stored
+                .into_iter()
+                .map(|s| s.transaction)
+                .collect::<Vec<_>>(),
+            vec![converted, plain]
+        );
+    }
+
+    fn with_ledgerbal(
+        mut statement: StatementTransactionResponse,
+        balamt: f64,
+        date: (i32, u32, u32),
+    ) -> StatementTransactionResponse {
+        statement.stmtrs.ledgerbal = Some(balance(balamt, date));
+        statement
+    }
+
+    fn fitids(store: &SqliteStore) -> Vec<(String, String)> {
+        store
+            .transactions(&account("1"))
+            .unwrap()
+            .into_iter()
+            .map(|s| (s.transaction.fitid, s.source))
+            .collect()
+    }
+
+    fn count(store: &SqliteStore, table: &str) -> i64 {
+        store
+            .connection
+            .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn imports_only_unknown_fitids() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        let first = document(vec![statement(
+            "1",
+            vec![
+                transaction("a", (2025, 6, 1), -10.0),
+                transaction("b", (2025, 6, 2), -20.0),
+            ],
+        )]);
+        let overlapping = document(vec![statement(
+            "1",
+            vec![
+                transaction("b", (2025, 6, 2), -20.0),
+                transaction("c", (2025, 6, 3), -30.0),
+            ],
+        )]);
+
+        let first = store.import_document(&first, "may.ofx").unwrap();
+        let second = store.import_document(&overlapping, "june.ofx").unwrap();
+
+        assert_eq!((first.added, first.skipped), (2, 0));
+        assert_eq!((second.added, second.skipped), (1, 1));
+        assert_eq!(
+            fitids(&store),
+            vec![
+                ("a".to_string(), "may.ofx".to_string()),
+                ("b".to_string(), "may.ofx".to_string()),
+                ("c".to_string(), "june.ofx".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn records_import_history() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        let doc = document(vec![statement(
+            "1",
+            vec![transaction("a", (2025, 6, 1), -10.0)],
+        )]);
+
+        let first = store.import_document(&doc, "a.ofx").unwrap();
+        let again = store.import_document(&doc, "copy of a.ofx").unwrap();
+
+        let imports = store.imports().unwrap();
+        assert_eq!(
+            imports
+                .iter()
+                .map(|i| (i.id, i.source.as_str(), i.added, i.skipped))
+                .collect::<Vec<_>>(),
+            vec![(first.id, "a.ofx", 1, 0), (again.id, "copy of a.ofx", 0, 1)]
+        );
+        assert_eq!(
+            imports[0].dtserver,
+            doc.body.sign_on_response.dtserver.datetime
+        );
+        assert_eq!(
+            store.transactions(&account("1")).unwrap()[0].import_id,
+            first.id
+        );
+    }
+
+    #[test]
+    fn reimporting_a_file_keeps_one_copy_of_its_statements() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        let doc = document(vec![with_ledgerbal(
+            statement("1", vec![transaction("a", (2025, 6, 1), -10.0)]),
+            90.0,
+            (2025, 6, 30),
+        )]);
+
+        store.import_document(&doc, "a.ofx").unwrap();
+        store.import_document(&doc, "a.ofx").unwrap();
+
+        assert_eq!(count(&store, "imports"), 2);
+        assert_eq!(count(&store, "statements"), 1);
+        assert_eq!(count(&store, "balances"), 1);
+        assert_eq!(count(&store, "transactions"), 1);
+    }
+
+    #[test]
+    fn latest_balance_prefers_later_dates_then_later_imports() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        let june = |fitid: &str, balamt: f64, date| {
+            document(vec![with_ledgerbal(
+                statement("1", vec![transaction(fitid, (2025, 6, 1), -10.0)]),
+                balamt,
+                date,
+            )])
+        };
+
+        store
+            .import_document(&june("a", 100.0, (2025, 6, 30)), "a.ofx")
+            .unwrap();
+        store
+            .import_document(&june("b", 80.0, (2025, 6, 15)), "b.ofx")
+            .unwrap();
+        assert_eq!(
+            store
+                .latest_balance(&account("1"), BalanceKind::Ledger)
+                .unwrap()
+                .map(|b| b.balamt),
+            Some(100.0)
+        );
+
+        store
+            .import_document(&june("c", 120.0, (2025, 6, 30)), "c.ofx")
+            .unwrap();
+        assert_eq!(
+            store
+                .latest_balance(&account("1"), BalanceKind::Ledger)
+                .unwrap()
+                .map(|b| b.balamt),
+            Some(120.0)
+        );
+        assert_eq!(
+            store
+                .latest_balance(&account("1"), BalanceKind::Available)
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn applies_corrections_across_imports() {
+        let mut store = SqliteStore::open_in_memory().unwrap();
+        let original = document(vec![statement(
+            "1",
+            vec![
+                transaction("a", (2025, 6, 1), -10.0),
+                transaction("b", (2025, 6, 2), -20.0),
+            ],
+        )]);
+        let correction = document(vec![statement(
+            "1",
+            vec![Transaction {
+                correctfitid: Some("a".to_string()),
+                correctaction: Some(CorrectAction::Replace),
+                ..transaction("a2", (2025, 6, 3), -12.0)
+            }],
+        )]);
+
+        store.import_document(&original, "a.ofx").unwrap();
+        store.import_document(&correction, "b.ofx").unwrap();
+
+        assert_eq!(
+            store
+                .effective_transactions(&account("1"))
+                .unwrap()
+                .iter()
+                .map(|t| (t.fitid.as_str(), t.trnamt))
+                .collect::<Vec<_>>(),
+            vec![("b", -20.0), ("a2", -12.0)]
+        );
+    }
+}
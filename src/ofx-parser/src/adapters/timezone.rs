@@ -0,0 +1,72 @@
+use chrono_tz::Tz;
+
+/// Abbreviations seen in OFX date brackets, mapped to the IANA zone they stand for.
+///
+/// Daylight and standard abbreviations denote a fixed offset, so they map to `Etc/GMT±N` zones
+/// rather than to a region whose offset would flip with the season. Note that the POSIX-style
+/// `Etc` names have their sign inverted: `Etc/GMT+3` is three hours behind UTC.
+///
+/// `IST` is the one exception. `Etc` zones only come in whole hours, so India's +05:30 maps to
+/// `Asia/Kolkata`, which has kept that offset all year since 1945. The abbreviation also stands
+/// for Irish summer time and Israel Standard Time, so it is listed in [`AMBIGUOUS`] too: India
+/// is only assumed when the bracket has no numeric offset saying otherwise.
+const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("UTC", "UTC"),
+    ("GMT", "UTC"),
+    ("Z", "UTC"),
+    ("BRT", "Etc/GMT+3"),
+    ("BRST", "Etc/GMT+2"),
+    ("AMT", "Etc/GMT+4"),
+    ("ART", "Etc/GMT+3"),
+    ("CLT", "Etc/GMT+4"),
+    ("EST", "Etc/GMT+5"),
+    ("EDT", "Etc/GMT+4"),
+    ("CST", "Etc/GMT+6"),
+    ("CDT", "Etc/GMT+5"),
+    ("MST", "Etc/GMT+7"),
+    ("MDT", "Etc/GMT+6"),
+    ("PST", "Etc/GMT+8"),
+    ("PDT", "Etc/GMT+7"),
+    ("AKST", "Etc/GMT+9"),
+    ("AKDT", "Etc/GMT+8"),
+    ("HST", "Etc/GMT+10"),
+    ("WET", "Etc/GMT"),
+    ("WEST", "Etc/GMT-1"),
+    ("BST", "Etc/GMT-1"),
+    ("CET", "Etc/GMT-1"),
+    ("CEST", "Etc/GMT-2"),
+    ("EET", "Etc/GMT-2"),
+    ("EEST", "Etc/GMT-3"),
+    ("MSK", "Etc/GMT-3"),
+    ("IST", "Asia/Kolkata"),
+    ("SGT", "Etc/GMT-8"),
+    ("HKT", "Etc/GMT-8"),
+    ("JST", "Etc/GMT-9"),
+    ("AEST", "Etc/GMT-10"),
+    ("AEDT", "Etc/GMT-11"),
+    ("NZST", "Etc/GMT-12"),
+    ("NZDT", "Etc/GMT-13"),
+];
+
+/// Abbreviations that stand for more than one zone, for which a numeric offset in the same
+/// bracket wins over the zone they are mapped to.
+const AMBIGUOUS: &[&str] = &["IST"];
+
+/// Whether a zone name could stand for more than one offset.
+pub fn is_ambiguous(name: &str) -> bool {
+    AMBIGUOUS
+        .iter()
+        .any(|a| a.eq_ignore_ascii_case(name.trim()))
+}
+
+/// Resolves the name part of an OFX date bracket, accepting either a full IANA identifier such
+/// as `America/Sao_Paulo` or one of the common abbreviations.
+pub fn resolve_zone(name: &str) -> Option<Tz> {
+    let name = name.trim();
+
+    ABBREVIATIONS
+        .iter()
+        .find(|(abbreviation, _)| abbreviation.eq_ignore_ascii_case(name))
+        .and_then(|(_, zone)| zone.parse::<Tz>().ok())
+        .or_else(|| name.parse::<Tz>().ok())
+}
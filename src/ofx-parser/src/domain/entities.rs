@@ -1 +1,3 @@
+pub mod annotation;
 pub mod ofx;
+pub mod profile;
@@ -0,0 +1,95 @@
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+
+use super::ofx::TransactionRef;
+
+/// Largest difference between the splits and the transaction amount that still counts as equal.
+const SPLIT_TOLERANCE: f64 = 0.005;
+
+/// Part of a transaction assigned to its own category.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Split {
+    pub category: String,
+    /// Signed like `TRNAMT`.
+    pub amount: f64,
+    pub memo: Option<String>,
+}
+
+/// What the user added to a transaction. The bank's data is never changed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Annotation {
+    pub splits: Vec<Split>,
+    pub notes: Option<String>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("Splits add up to {total:.2} but the transaction amount is {trnamt:.2}")]
+pub struct SplitMismatch {
+    pub total: f64,
+    pub trnamt: f64,
+}
+
+impl Annotation {
+    pub fn is_empty(&self) -> bool {
+        self.splits.is_empty() && self.notes.is_none() && self.tags.is_empty()
+    }
+
+    /// Splits, when there are any, have to add up to the transaction amount.
+    pub fn check_splits(&self, trnamt: f64) -> Result<(), SplitMismatch> {
+        if self.splits.is_empty() {
+            return Ok(());
+        }
+        let total: f64 = self.splits.iter().map(|s| s.amount).sum();
+        if (total - trnamt).abs() > SPLIT_TOLERANCE {
+            return Err(SplitMismatch { total, trnamt });
+        }
+        Ok(())
+    }
+}
+
+/// Annotations keyed by account and `FITID`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Annotations {
+    entries: BTreeMap<TransactionRef, Annotation>,
+}
+
+impl Annotations {
+    pub fn get(&self, reference: &TransactionRef) -> Option<&Annotation> {
+        self.entries.get(reference)
+    }
+
+    /// Stores an annotation as is, as when reading back saved ones.
+    pub fn insert(&mut self, reference: TransactionRef, annotation: Annotation) {
+        self.entries.insert(reference, annotation);
+    }
+
+    /// Stores an annotation for a transaction of amount `trnamt`, or removes it when empty.
+    pub fn annotate(
+        &mut self,
+        reference: TransactionRef,
+        annotation: Annotation,
+        trnamt: f64,
+    ) -> Result<(), SplitMismatch> {
+        annotation.check_splits(trnamt)?;
+        if annotation.is_empty() {
+            self.entries.remove(&reference);
+        } else {
+            self.entries.insert(reference, annotation);
+        }
+        Ok(())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&TransactionRef, &Annotation)> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
@@ -1,4 +1,8 @@
-use chrono::{DateTime, FixedOffset};
+use std::{collections::BTreeMap, fmt};
+
+use chrono::{DateTime, FixedOffset, TimeZone};
+
+use super::profile::{ProfileResponse, ProfileTransactionResponse};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct OfxHeader {
@@ -11,6 +15,58 @@ pub struct OfxHeader {
     pub new_file_uid: Option<String>,
 }
 
+impl Default for OfxHeader {
+    /// The header of a plain OFX 1.02 document, assumed when lenient parsing finds none.
+    fn default() -> Self {
+        OfxHeader {
+            version: "102".to_string(),
+            security: None,
+            encoding: None,
+            charset: None,
+            compression: None,
+            old_file_uid: None,
+            new_file_uid: None,
+        }
+    }
+}
+
+/// An OFX timestamp along with the zone name its `[offset:NAME]` bracket carried.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OfxDateTime {
+    pub datetime: DateTime<FixedOffset>,
+    pub tz_name: Option<String>,
+    /// The timestamp as written in the file, such as `20250604000000[-3:BRT]`, when it was
+    /// parsed from one.
+    pub raw: Option<String>,
+}
+
+impl OfxDateTime {
+    /// The same instant expressed in another zone. The original zone name and text are kept.
+    pub fn with_timezone<Tz: TimeZone>(&self, tz: &Tz) -> OfxDateTime {
+        OfxDateTime {
+            datetime: self.datetime.with_timezone(tz).fixed_offset(),
+            tz_name: self.tz_name.clone(),
+            raw: self.raw.clone(),
+        }
+    }
+}
+
+impl From<DateTime<FixedOffset>> for OfxDateTime {
+    fn from(datetime: DateTime<FixedOffset>) -> Self {
+        OfxDateTime {
+            datetime,
+            tz_name: None,
+            raw: None,
+        }
+    }
+}
+
+impl fmt::Display for OfxDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.datetime.fmt(f)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct SignonMessage {
     pub sonrs: SignonResponse,
@@ -19,10 +75,38 @@ pub struct SignonMessage {
 #[derive(Debug, Clone, PartialEq)]
 pub struct SignonResponse {
     pub status: Status,
-    pub dtserver: DateTime<FixedOffset>,
+    pub dtserver: OfxDateTime,
     pub language: Option<String>,
-    pub dtprofup: Option<DateTime<FixedOffset>>,
+    pub dtprofup: Option<OfxDateTime>,
     pub fi: Option<FinancialInstitution>,
+    pub extensions: IntuitExtensions,
+    pub unknown: Vec<UnknownElement>,
+}
+
+/// Quicken (QFX) vendor elements, i.e. the `INTU.*` children of an aggregate.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IntuitExtensions {
+    /// `INTU.BID`, Intuit's identifier for the bank.
+    pub bid: Option<String>,
+    /// `INTU.USERID`
+    pub userid: Option<String>,
+    /// Every other `INTU.*` element keyed by its full tag. Nested aggregates are flattened
+    /// into `/`-separated paths of full tags such as `INTU.XFER/INTU.DTXFER`.
+    pub other: BTreeMap<String, String>,
+}
+
+impl IntuitExtensions {
+    pub fn is_empty(&self) -> bool {
+        self.bid.is_none() && self.userid.is_none() && self.other.is_empty()
+    }
+
+    pub fn get(&self, tag: &str) -> Option<&str> {
+        match tag {
+            "INTU.BID" => self.bid.as_deref(),
+            "INTU.USERID" => self.userid.as_deref(),
+            _ => self.other.get(tag).map(String::as_str),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -30,12 +114,14 @@ pub struct Status {
     pub code: i32,
     pub severity: String,
     pub message: Option<String>,
+    pub unknown: Vec<UnknownElement>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct FinancialInstitution {
     pub org: String,
     pub fid: Option<String>,
+    pub unknown: Vec<UnknownElement>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -43,23 +129,76 @@ pub struct BankAccount {
     pub bankid: String,
     pub acctid: String,
     pub accttype: String,
+    pub unknown: Vec<UnknownElement>,
+}
+
+impl BankAccount {
+    pub fn key(&self) -> AccountKey {
+        AccountKey {
+            bankid: self.bankid.clone(),
+            acctid: self.acctid.clone(),
+            accttype: self.accttype.clone(),
+        }
+    }
+}
+
+/// Identity of a `BANKACCTFROM`, usable as a map key across documents.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AccountKey {
+    pub bankid: String,
+    pub acctid: String,
+    pub accttype: String,
+}
+
+/// Identifies a transaction across statements.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TransactionRef {
+    pub account: AccountKey,
+    pub fitid: String,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Transaction {
     pub trntype: String,
-    pub dtposted: DateTime<FixedOffset>,
+    pub dtposted: OfxDateTime,
     pub trnamt: f64,
     pub fitid: String,
     pub name: Option<String>,
     pub memo: Option<String>,
+    /// `FITID` of an earlier transaction this one corrects.
+    pub correctfitid: Option<String>,
+    pub correctaction: Option<CorrectAction>,
+    /// Currency of the amount, when it differs from the statement's `CURDEF`.
+    pub currency: Option<Currency>,
+    /// Currency the transaction was made in, when the amount was converted to `CURDEF`.
+    pub origcurrency: Option<Currency>,
+    pub unknown: Vec<UnknownElement>,
+}
+
+/// `CURRENCY` or `ORIGCURRENCY` aggregate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Currency {
+    /// Rate from this currency to `CURDEF`.
+    pub currate: f64,
+    /// ISO 4217 code.
+    pub cursym: String,
+    pub unknown: Vec<UnknownElement>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorrectAction {
+    /// This transaction takes the place of the one named by `correctfitid`.
+    Replace,
+    /// The transaction named by `correctfitid` should be dropped. This one carries no data.
+    Delete,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct BankTransactionList {
-    pub dtstart: DateTime<FixedOffset>,
-    pub dtend: DateTime<FixedOffset>,
+    pub dtstart: OfxDateTime,
+    pub dtend: OfxDateTime,
     pub transactions: Vec<Transaction>,
+    pub unknown: Vec<UnknownElement>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -67,6 +206,7 @@ pub struct StatementTransactionResponse {
     pub trnuid: String,
     pub status: Status,
     pub stmtrs: StatementResponse,
+    pub unknown: Vec<UnknownElement>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -76,22 +216,137 @@ pub struct StatementResponse {
     pub banktranlist: Option<BankTransactionList>,
     pub ledgerbal: Option<Balance>,
     pub availbal: Option<Balance>,
+    pub extensions: IntuitExtensions,
+    pub unknown: Vec<UnknownElement>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Balance {
     pub balamt: f64,
-    pub dtasof: DateTime<FixedOffset>,
+    pub dtasof: OfxDateTime,
+    pub unknown: Vec<UnknownElement>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct OfxBody {
     pub sign_on_response: SignonResponse,
     pub bank_msgs: Vec<StatementTransactionResponse>,
+    pub profile_msgs: Vec<ProfileTransactionResponse>,
+    /// Unknown children of `OFX` itself.
+    pub unknown: Vec<UnknownElement>,
+    /// Unknown children of message set wrappers such as `BANKMSGSRSV1`, keyed by the wrapper's
+    /// tag.
+    pub message_set_unknown: BTreeMap<String, Vec<UnknownElement>>,
+}
+
+impl OfxBody {
+    /// The first FI profile carried in `PROFMSGSRSV1`, if the server sent one.
+    pub fn profile(&self) -> Option<&ProfileResponse> {
+        self.profile_msgs.iter().find_map(|p| p.profrs.as_ref())
+    }
+}
+
+/// An element the parser does not model, kept so that its data is not lost.
+///
+/// `value` is meant for reading, with whitespace trimmed and entities resolved, while `text` and
+/// `attributes` are as written. The exact layout of the element within the file, down to the
+/// whitespace between its children, is in [`OfxDocument::source`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownElement {
+    /// Tag of the element this one was found in. Usually the owning aggregate's own tag, but
+    /// aggregates that absorb a nested element (such as `MSGSETCORE`) carry that tag instead.
+    pub parent: String,
+    /// Index among all of the parent's children, known ones included, so a writer can put the
+    /// element back where it was.
+    pub position: usize,
+    pub tag: String,
+    pub value: Option<String>,
+    /// Text as written, whitespace, entities and comments included. The pieces between
+    /// children run together.
+    pub text: Option<String>,
+    /// Attributes as written, values still escaped.
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<UnknownElement>,
+}
+
+/// An element exactly as written, so a writer can reproduce a file byte for byte, modelled
+/// values included. The file itself is kept as an element without tags of its own.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceElement {
+    /// Empty for the file itself.
+    pub tag: String,
+    /// Start tag as written, attributes included.
+    pub start: String,
+    /// Text around the children as written, comments and declarations included: one piece
+    /// before each child and a last one before the end tag.
+    pub text: Vec<String>,
+    pub children: Vec<SourceElement>,
+    /// End tag as written, none for empty-element tags and elements SGML leaves open.
+    pub end: Option<String>,
+}
+
+impl SourceElement {
+    /// Follows a `/`-separated path of child tags, always taking the first match.
+    pub fn find(&self, path: &str) -> Option<&SourceElement> {
+        path.split('/').try_fold(self, |element, tag| {
+            element.children.iter().find(|c| c.tag == tag)
+        })
+    }
+}
+
+impl fmt::Display for SourceElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.start)?;
+        for (text, child) in self.text.iter().zip(&self.children) {
+            f.write_str(text)?;
+            write!(f, "{child}")?;
+        }
+        if let Some(text) = self.text.get(self.children.len()) {
+            f.write_str(text)?;
+        }
+        f.write_str(self.end.as_deref().unwrap_or_default())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct OfxDocument {
     pub header: OfxHeader,
     pub body: OfxBody,
+    /// The file the document was read from, none for documents built in code.
+    pub source: Option<SourceElement>,
+}
+
+impl OfxDocument {
+    /// Re-expresses every date in the document in the given zone.
+    pub fn normalize_timezone<Tz: TimeZone>(&mut self, tz: &Tz) {
+        let convert = |date: &mut OfxDateTime| *date = date.with_timezone(tz);
+
+        let sonrs = &mut self.body.sign_on_response;
+        convert(&mut sonrs.dtserver);
+        sonrs.dtprofup.iter_mut().for_each(convert);
+
+        for stmtrs in self.body.bank_msgs.iter_mut().map(|m| &mut m.stmtrs) {
+            if let Some(list) = stmtrs.banktranlist.as_mut() {
+                convert(&mut list.dtstart);
+                convert(&mut list.dtend);
+                list.transactions
+                    .iter_mut()
+                    .for_each(|t| convert(&mut t.dtposted));
+            }
+            stmtrs
+                .ledgerbal
+                .iter_mut()
+                .chain(stmtrs.availbal.iter_mut())
+                .for_each(|b| convert(&mut b.dtasof));
+        }
+
+        for profile in self
+            .body
+            .profile_msgs
+            .iter_mut()
+            .filter_map(|p| p.profrs.as_mut())
+        {
+            convert(&mut profile.dtprofup);
+        }
+    }
 }
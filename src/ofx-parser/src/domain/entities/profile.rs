@@ -0,0 +1,145 @@
+use super::ofx::{OfxDateTime, Status, UnknownElement};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileTransactionResponse {
+    pub trnuid: String,
+    pub status: Status,
+    pub profrs: Option<ProfileResponse>,
+    pub unknown: Vec<UnknownElement>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileResponse {
+    pub message_sets: Vec<MessageSet>,
+    pub signon_infos: Vec<SignonInfo>,
+    pub dtprofup: OfxDateTime,
+    pub finame: String,
+    pub address: Vec<String>,
+    pub city: String,
+    pub state: String,
+    pub postalcode: String,
+    pub country: Option<String>,
+    pub csphone: Option<String>,
+    pub tsphone: Option<String>,
+    pub faxphone: Option<String>,
+    pub url: Option<String>,
+    pub email: Option<String>,
+    pub unknown: Vec<UnknownElement>,
+}
+
+impl ProfileResponse {
+    /// Returns the most recent version the FI advertises for the given message set.
+    pub fn message_set(&self, kind: MessageSetKind) -> Option<&MessageSet> {
+        self.message_sets
+            .iter()
+            .filter(|m| m.kind == kind)
+            .max_by_key(|m| m.ver)
+    }
+
+    pub fn supports(&self, kind: MessageSetKind) -> bool {
+        self.message_set(kind).is_some()
+    }
+
+    pub fn supports_bank_download(&self) -> bool {
+        self.supports(MessageSetKind::Bank)
+    }
+
+    pub fn supports_credit_card_download(&self) -> bool {
+        self.supports(MessageSetKind::CreditCard)
+    }
+
+    pub fn supports_investment_download(&self) -> bool {
+        self.supports(MessageSetKind::Investment)
+    }
+
+    pub fn signon_info(&self, realm: &str) -> Option<&SignonInfo> {
+        self.signon_infos.iter().find(|s| s.signonrealm == realm)
+    }
+
+    /// Password rules that apply when signing on to use the given message set.
+    pub fn password_constraints(&self, kind: MessageSetKind) -> Option<&SignonInfo> {
+        self.message_set(kind)
+            .and_then(|m| self.signon_info(&m.signonrealm))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageSetKind {
+    Signon,
+    Signup,
+    Bank,
+    CreditCard,
+    Investment,
+    InterTransfer,
+    WireTransfer,
+    BillPay,
+    Email,
+    SecurityList,
+    Profile,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageSet {
+    pub kind: MessageSetKind,
+    pub ver: u32,
+    pub url: String,
+    pub ofxsec: String,
+    pub transpsec: bool,
+    pub signonrealm: String,
+    pub language: Vec<String>,
+    pub syncmode: String,
+    pub respfileer: bool,
+    pub spname: Option<String>,
+    pub closingavail: Option<bool>,
+    pub unknown: Vec<UnknownElement>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharType {
+    AlphaOnly,
+    NumericOnly,
+    AlphaOrNumeric,
+    AlphaAndNumeric,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignonInfo {
+    pub signonrealm: String,
+    pub min: u32,
+    pub max: u32,
+    pub chartype: CharType,
+    pub casesen: bool,
+    pub special: bool,
+    pub spaces: bool,
+    pub pinch: bool,
+    pub chgpinfirst: bool,
+    pub unknown: Vec<UnknownElement>,
+}
+
+impl SignonInfo {
+    /// Checks a candidate password against the realm's length and character rules.
+    pub fn accepts(&self, password: &str) -> bool {
+        let len = password.chars().count() as u32;
+        if len < self.min || len > self.max {
+            return false;
+        }
+
+        let has_alpha = password.chars().any(|c| c.is_alphabetic());
+        let has_digit = password.chars().any(|c| c.is_ascii_digit());
+        let has_space = password.chars().any(|c| c.is_whitespace());
+        let has_special = password
+            .chars()
+            .any(|c| !c.is_alphanumeric() && !c.is_whitespace());
+
+        if (has_space && !self.spaces) || (has_special && !self.special) {
+            return false;
+        }
+
+        match self.chartype {
+            CharType::AlphaOnly => !has_digit,
+            CharType::NumericOnly => !has_alpha,
+            CharType::AlphaOrNumeric => true,
+            CharType::AlphaAndNumeric => has_alpha && has_digit,
+        }
+    }
+}
@@ -0,0 +1,13 @@
+pub mod anomalies;
+pub mod budget;
+pub mod categorization;
+pub mod corrections;
+pub mod filter;
+pub mod forecast;
+pub mod merge;
+pub mod payee;
+pub mod recurring;
+pub mod reports;
+pub mod running_balance;
+pub mod transfers;
+pub mod validation;
@@ -0,0 +1,293 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+};
+
+use chrono::TimeDelta;
+
+use crate::domain::{
+    entities::ofx::{AccountKey, Transaction},
+    services::{categorization::Categorizer, payee::PayeeNormalizer},
+};
+
+#[derive(Debug, Clone)]
+pub struct AnomalyOptions {
+    /// How many standard deviations above the average of its payee or category a charge has to
+    /// be to stand out.
+    pub std_devs: f64,
+    /// Fewest earlier or later charges a payee or category needs before its average is trusted.
+    pub min_history: usize,
+    /// Smallest first charge from a merchant that gets flagged.
+    pub large_charge: f64,
+    /// Merchants first seen this many days into the transactions are all new to the statements,
+    /// not to the account holder, so they are not flagged.
+    pub warmup_days: i64,
+    /// Longest gap between two identical charges for the second to count as a duplicate.
+    pub duplicate_window: TimeDelta,
+    pub normalizer: PayeeNormalizer,
+    pub categorizer: Categorizer,
+}
+
+impl Default for AnomalyOptions {
+    fn default() -> Self {
+        AnomalyOptions {
+            std_devs: 3.0,
+            min_history: 4,
+            large_charge: 500.0,
+            warmup_days: 30,
+            duplicate_window: TimeDelta::minutes(10),
+            normalizer: PayeeNormalizer::default(),
+            categorizer: Categorizer::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AnomalyReason {
+    UnusualForPayee {
+        average: f64,
+        std_devs: f64,
+    },
+    UnusualForCategory {
+        category: String,
+        average: f64,
+        std_devs: f64,
+    },
+    NewMerchant,
+    /// Same payee and amount as the charge with `FITID` `of`, posted `minutes` earlier.
+    Duplicate {
+        of: String,
+        minutes: i64,
+    },
+    ForeignCurrency {
+        cursym: String,
+        curdef: String,
+    },
+}
+
+impl fmt::Display for AnomalyReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnomalyReason::UnusualForPayee { average, std_devs } => write!(
+                f,
+                "{std_devs:.1} std devs above the usual {average:.2} for this payee"
+            ),
+            AnomalyReason::UnusualForCategory {
+                category,
+                average,
+                std_devs,
+            } => write!(
+                f,
+                "{std_devs:.1} std devs above the usual {average:.2} for {category}"
+            ),
+            AnomalyReason::NewMerchant => write!(f, "Large first charge from this merchant"),
+            AnomalyReason::Duplicate { of, minutes } => {
+                write!(f, "Same charge as {of} {minutes} min earlier")
+            }
+            AnomalyReason::ForeignCurrency { cursym, curdef } => {
+                write!(f, "Charged in {cursym} on a {curdef} account")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anomaly {
+    pub fitid: String,
+    pub reasons: Vec<AnomalyReason>,
+}
+
+/// Flags charges that are unusual for an account whose statements are in `curdef`.
+///
+/// Only debits are looked at. A charge is compared against every other charge of its payee,
+/// and of its category when a rule categorizes it, so one outlier does not hide another.
+/// Anomalies are returned in posting order.
+pub fn detect_anomalies(
+    account: &AccountKey,
+    curdef: &str,
+    transactions: &[Transaction],
+    options: &AnomalyOptions,
+) -> Vec<Anomaly> {
+    let mut charges: Vec<&Transaction> = transactions.iter().filter(|t| t.trnamt < 0.0).collect();
+    charges.sort_by_key(|t| t.dtposted.datetime);
+    let Some(first) = charges.first().map(|t| t.dtposted.datetime) else {
+        return Vec::new();
+    };
+
+    let payees: Vec<String> = charges
+        .iter()
+        .map(|t| options.normalizer.payee(t))
+        .collect();
+    let categories: Vec<Option<String>> = charges
+        .iter()
+        .map(|t| {
+            options
+                .categorizer
+                .categorize(t, Some(account))
+                .map(|c| c.category)
+        })
+        .collect();
+    let mut reasons: Vec<Vec<AnomalyReason>> = vec![Vec::new(); charges.len()];
+
+    let by_payee = group_indexes(payees.iter().map(Some));
+    for (index, average, std_devs) in outliers(&charges, &by_payee, options) {
+        reasons[index].push(AnomalyReason::UnusualForPayee { average, std_devs });
+    }
+    let by_category = group_indexes(categories.iter().map(Option::as_ref));
+    for (index, average, std_devs) in outliers(&charges, &by_category, options) {
+        reasons[index].push(AnomalyReason::UnusualForCategory {
+            category: categories[index].clone().unwrap_or_default(),
+            average,
+            std_devs,
+        });
+    }
+
+    let mut seen: HashSet<&str> = HashSet::new();
+    for (index, charge) in charges.iter().enumerate() {
+        let new = seen.insert(&payees[index]);
+        let after_warmup = (charge.dtposted.datetime - first).num_days() >= options.warmup_days;
+        if new && after_warmup && -charge.trnamt >= options.large_charge {
+            reasons[index].push(AnomalyReason::NewMerchant);
+        }
+
+        let duplicate = charges[..index]
+            .iter()
+            .enumerate()
+            .rev()
+            .take_while(|(_, earlier)| {
+                charge.dtposted.datetime - earlier.dtposted.datetime <= options.duplicate_window
+            })
+            .find(|&(i, earlier)| payees[i] == payees[index] && earlier.trnamt == charge.trnamt);
+        if let Some((_, earlier)) = duplicate {
+            reasons[index].push(AnomalyReason::Duplicate {
+                of: earlier.fitid.clone(),
+                minutes: (charge.dtposted.datetime - earlier.dtposted.datetime).num_minutes(),
+            });
+        }
+
+        let foreign = [&charge.origcurrency, &charge.currency]
+            .into_iter()
+            .flatten()
+            .find(|c| !c.cursym.eq_ignore_ascii_case(curdef));
+        if let Some(currency) = foreign {
+            reasons[index].push(AnomalyReason::ForeignCurrency {
+                cursym: currency.cursym.clone(),
+                curdef: curdef.to_string(),
+            });
+        }
+    }
+
+    charges
+        .into_iter()
+        .zip(reasons)
+        .filter(|(_, reasons)| !reasons.is_empty())
+        .map(|(charge, reasons)| Anomaly {
+            fitid: charge.fitid.clone(),
+            reasons,
+        })
+        .collect()
+}
+
+fn group_indexes<'a>(keys: impl Iterator<Item = Option<&'a String>>) -> Vec<Vec<usize>> {
+    let mut groups: BTreeMap<&String, Vec<usize>> = BTreeMap::new();
+    for (index, key) in keys.enumerate() {
+        if let Some(key) = key {
+            groups.entry(key).or_default().push(index);
+        }
+    }
+    groups.into_values().collect()
+}
+
+/// Charges of each group far above the average of the others, with that average and how many
+/// standard deviations above it they are.
+fn outliers(
+    charges: &[&Transaction],
+    groups: &[Vec<usize>],
+    options: &AnomalyOptions,
+) -> Vec<(usize, f64, f64)> {
+    let mut found = Vec::new();
+    for group in groups.iter().filter(|g| g.len() > options.min_history) {
+        let amounts: Vec<f64> = group.iter().map(|&i| -charges[i].trnamt).collect();
+        let sum: f64 = amounts.iter().sum();
+        let squares: f64 = amounts.iter().map(|a| a * a).sum();
+        let others = (amounts.len() - 1) as f64;
+
+        for (&index, &amount) in group.iter().zip(&amounts) {
+            let average = (sum - amount) / others;
+            let variance = ((squares - amount * amount) / others - average * average).max(0.0);
+            // Charges that never change would make any difference infinitely unusual
+            let std_dev = variance.sqrt().max(average * 0.1);
+            let std_devs = (amount - average) / std_dev;
+            if std_dev > 0.0 && std_devs >= options.std_devs {
+                found.push((index, average, std_devs));
+            }
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::entities::ofx::Currency,
+        test_support::{self, account, datetime_at},
+    };
+    use pretty_assertions::assert_eq;
+
+    fn transaction(
+        fitid: &str,
+        (m, d, min): (u32, u32, u32),
+        trnamt: f64,
+        name: &str,
+    ) -> Transaction {
+        Transaction {
+            trntype: "POS".to_string(),
+            dtposted: datetime_at((2025, m, d), 12, min),
+            name: Some(name.to_string()),
+            ..test_support::transaction(fitid, (2025, m, d), trnamt)
+        }
+    }
+
+    #[test]
+    fn flags_outliers_new_merchants_duplicates_and_foreign_charges() {
+        let account = account("12345");
+        let mut transactions = vec![
+            transaction("u1", (1, 3, 0), -25.0, "UBER TRIP"),
+            transaction("u2", (1, 9, 0), -31.0, "UBER TRIP"),
+            transaction("u3", (1, 20, 0), -28.0, "UBER TRIP"),
+            transaction("u4", (2, 2, 0), -22.0, "UBER TRIP"),
+            transaction("u5", (2, 14, 0), -30.0, "UBER TRIP"),
+            transaction("u6", (3, 1, 0), -190.0, "UBER TRIP"),
+            transaction("c1", (3, 5, 0), -18.0, "CAFE"),
+            transaction("c2", (3, 5, 4), -18.0, "CAFE"),
+            transaction("t1", (3, 8, 0), -1200.0, "TV STORE"),
+            transaction("s1", (3, 10, 0), -52.10, "STEAM"),
+        ];
+        transactions[9].origcurrency = Some(Currency {
+            currate: 5.21,
+            cursym: "USD".to_string(),
+            unknown: Vec::new(),
+        });
+
+        let anomalies =
+            detect_anomalies(&account, "BRL", &transactions, &AnomalyOptions::default());
+
+        let flagged: Vec<(&str, String)> = anomalies
+            .iter()
+            .map(|a| (a.fitid.as_str(), a.reasons[0].to_string()))
+            .collect();
+        assert_eq!(
+            flagged,
+            vec![
+                (
+                    "u6",
+                    "49.2 std devs above the usual 27.20 for this payee".to_string()
+                ),
+                ("c2", "Same charge as c1 4 min earlier".to_string()),
+                ("t1", "Large first charge from this merchant".to_string()),
+                ("s1", "Charged in USD on a BRL account".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use chrono::{Months, NaiveDate};
+
+use crate::domain::{
+    entities::ofx::{AccountKey, Transaction},
+    services::reports::{GroupBy, Period, ReportOptions, summarize},
+};
+
+/// What happens to the rest of a month's budget when the month ends.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rollover {
+    /// Every month starts from the limit.
+    #[default]
+    None,
+    /// Money left over is added to next month, overspending is forgiven.
+    Unspent,
+    /// Money left over is added to next month and overspending is taken from it.
+    Full,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CategoryBudget {
+    pub category: String,
+    /// Monthly amount, positive.
+    pub limit: f64,
+    pub rollover: Rollover,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Budget {
+    pub categories: Vec<CategoryBudget>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetLine {
+    pub category: String,
+    pub limit: f64,
+    /// Brought over from the month before, negative after overspending with full rollover.
+    pub carried: f64,
+    /// Net spending, so refunds bring it down.
+    pub spent: f64,
+}
+
+impl BudgetLine {
+    pub fn available(&self) -> f64 {
+        self.limit + self.carried
+    }
+
+    pub fn remaining(&self) -> f64 {
+        self.available() - self.spent
+    }
+
+    /// Spent over available, above 1.0 when over budget.
+    pub fn used(&self) -> f64 {
+        if self.available() > 0.0 {
+            self.spent / self.available()
+        } else if self.spent > 0.0 {
+            f64::INFINITY
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthBudget {
+    /// First day of the month.
+    pub month: NaiveDate,
+    /// In the order of the budget.
+    pub lines: Vec<BudgetLine>,
+    /// Net spending in categories without a budget, uncategorized included. Categories that
+    /// took in more than they spent count as zero.
+    pub unbudgeted: f64,
+}
+
+/// Compares each month from the first transaction to the last against the budget.
+///
+/// Spending per category comes from a monthly category report, so rules, splits and the
+/// exclusion of transfers in `options` apply. Months without transactions are still listed,
+/// so rollover carries over them.
+pub fn budget_vs_actual<'a>(
+    budget: &Budget,
+    transactions: impl IntoIterator<Item = (&'a AccountKey, &'a Transaction)>,
+    options: &ReportOptions,
+) -> Vec<MonthBudget> {
+    let options = ReportOptions {
+        period: Period::Month,
+        group_by: GroupBy::Category,
+        ..options.clone()
+    };
+    let report = summarize(transactions, &options);
+    let (Some(first), Some(last)) = (report.periods.first(), report.periods.last()) else {
+        return Vec::new();
+    };
+
+    let mut carried = vec![0.0; budget.categories.len()];
+    let mut months = Vec::new();
+    let mut summaries = report.periods.iter().peekable();
+    let mut month = first.start;
+
+    while month <= last.start {
+        let summary = summaries.next_if(|s| s.start == month);
+        let spent = |category: &str| {
+            summary
+                .and_then(|s| s.groups.get(category))
+                .map_or(0.0, |t| -t.net())
+        };
+
+        let lines: Vec<BudgetLine> = budget
+            .categories
+            .iter()
+            .zip(&carried)
+            .map(|(c, &carried)| BudgetLine {
+                category: c.category.clone(),
+                limit: c.limit,
+                carried,
+                spent: spent(&c.category),
+            })
+            .collect();
+
+        carried = budget
+            .categories
+            .iter()
+            .zip(&lines)
+            .map(|(c, line)| match c.rollover {
+                Rollover::None => 0.0,
+                Rollover::Unspent => line.remaining().max(0.0),
+                Rollover::Full => line.remaining(),
+            })
+            .collect();
+
+        let unbudgeted = summary.map_or(0.0, |s| {
+            s.groups
+                .iter()
+                .filter(|(g, _)| !budget.categories.iter().any(|c| &c.category == *g))
+                .map(|(_, t)| (-t.net()).max(0.0))
+                .sum()
+        });
+
+        months.push(MonthBudget {
+            month,
+            lines,
+            unbudgeted,
+        });
+        month = month + Months::new(1);
+    }
+
+    months
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::services::categorization::{Categorizer, Rule, RuleConditions, TextMatch},
+        test_support::{self, account},
+    };
+    use pretty_assertions::assert_eq;
+
+    fn transaction(month: u32, trnamt: f64) -> Transaction {
+        Transaction {
+            trntype: "POS".to_string(),
+            name: Some("MARKET".to_string()),
+            ..test_support::transaction(&month.to_string(), (2025, month, 10), trnamt)
+        }
+    }
+
+    #[test]
+    fn carries_over_through_months_without_spending() {
+        let account = account("12345");
+        let budget = Budget {
+            categories: vec![
+                CategoryBudget {
+                    category: "Groceries".to_string(),
+                    limit: 100.0,
+                    rollover: Rollover::Full,
+                },
+                CategoryBudget {
+                    category: "Fun".to_string(),
+                    limit: 50.0,
+                    rollover: Rollover::Unspent,
+                },
+            ],
+        };
+        let options = ReportOptions {
+            categorizer: Categorizer::new(vec![Rule {
+                name: "Market".to_string(),
+                conditions: RuleConditions {
+                    name: Some(TextMatch::Contains("market".to_string())),
+                    ..Default::default()
+                },
+                category: "Groceries".to_string(),
+                tags: Vec::new(),
+            }]),
+            ..Default::default()
+        };
+        let transactions = [transaction(1, -130.0), transaction(3, -60.0)];
+
+        let months = budget_vs_actual(
+            &budget,
+            transactions.iter().map(|t| (&account, t)),
+            &options,
+        );
+
+        let groceries: Vec<(f64, f64)> = months
+            .iter()
+            .map(|m| (m.lines[0].carried, m.lines[0].remaining()))
+            .collect();
+        assert_eq!(groceries, vec![(0.0, -30.0), (-30.0, 70.0), (70.0, 110.0)]);
+        let fun: Vec<f64> = months.iter().map(|m| m.lines[1].carried).collect();
+        assert_eq!(fun, vec![0.0, 50.0, 100.0]);
+    }
+}
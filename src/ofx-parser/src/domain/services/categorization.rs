@@ -0,0 +1,106 @@
+use chrono::NaiveDate;
+use regex::Regex;
+
+use crate::domain::entities::ofx::{AccountKey, Transaction};
+
+/// How a rule compares against a text field.
+#[derive(Debug, Clone)]
+pub enum TextMatch {
+    /// Case-insensitive substring.
+    Contains(String),
+    Regex(Regex),
+}
+
+impl TextMatch {
+    pub fn matches(&self, text: &str) -> bool {
+        match self {
+            TextMatch::Contains(needle) => text.to_lowercase().contains(&needle.to_lowercase()),
+            TextMatch::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+/// Conditions a transaction has to meet for a rule to fire. Unset conditions always hold.
+#[derive(Debug, Clone, Default)]
+pub struct RuleConditions {
+    pub name: Option<TextMatch>,
+    pub memo: Option<TextMatch>,
+    pub trntype: Option<TextMatch>,
+    /// Matched against `ACCTID`.
+    pub account: Option<TextMatch>,
+    /// Inclusive bounds on `TRNAMT`, so debits are negative.
+    pub min_amount: Option<f64>,
+    pub max_amount: Option<f64>,
+    /// Inclusive bounds on the posting date.
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub name: String,
+    pub conditions: RuleConditions,
+    pub category: String,
+    pub tags: Vec<String>,
+}
+
+impl Rule {
+    pub fn matches(&self, transaction: &Transaction, account: Option<&AccountKey>) -> bool {
+        let c = &self.conditions;
+        let text = |condition: &Option<TextMatch>, value: Option<&str>| {
+            condition
+                .as_ref()
+                .is_none_or(|m| value.is_some_and(|v| m.matches(v)))
+        };
+        let day = transaction.dtposted.datetime.date_naive();
+
+        text(&c.name, transaction.name.as_deref())
+            && text(&c.memo, transaction.memo.as_deref())
+            && text(&c.trntype, Some(&transaction.trntype))
+            && text(&c.account, account.map(|a| a.acctid.as_str()))
+            && c.min_amount.is_none_or(|min| transaction.trnamt >= min)
+            && c.max_amount.is_none_or(|max| transaction.trnamt <= max)
+            && c.from.is_none_or(|from| day >= from)
+            && c.to.is_none_or(|to| day <= to)
+    }
+}
+
+/// The outcome of categorizing one transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Categorization {
+    pub category: String,
+    pub tags: Vec<String>,
+    /// Name of the rule that fired.
+    pub rule: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Categorizer {
+    rules: Vec<Rule>,
+}
+
+impl Categorizer {
+    pub fn new(rules: Vec<Rule>) -> Self {
+        Categorizer { rules }
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Rules are tried in order and the first one that matches wins.
+    pub fn categorize(
+        &self,
+        transaction: &Transaction,
+        account: Option<&AccountKey>,
+    ) -> Option<Categorization> {
+        self.rules
+            .iter()
+            .find(|r| r.matches(transaction, account))
+            .map(|r| Categorization {
+                category: r.category.clone(),
+                tags: r.tags.clone(),
+                rule: r.name.clone(),
+            })
+    }
+}
@@ -0,0 +1,149 @@
+use std::collections::{BTreeMap, HashSet};
+
+use log::{debug, warn};
+
+use crate::domain::entities::ofx::{
+    AccountKey, BankTransactionList, CorrectAction, OfxDocument, Transaction,
+};
+
+/// Applies `CORRECTACTION` entries in order, returning the transactions that remain.
+///
+/// A `REPLACE` drops the transaction it names and keeps itself, a `DELETE` drops both. A
+/// corrected FITID stays dropped: the target may arrive after its correction, as happens when
+/// files are fed out of order, or show up again in a later overlapping statement.
+pub fn apply_corrections<'a>(
+    transactions: impl IntoIterator<Item = &'a Transaction>,
+) -> Vec<Transaction> {
+    let mut effective: Vec<Transaction> = Vec::new();
+    // Every FITID a correction has named, so a later copy of the original stays dropped
+    let mut corrected: HashSet<String> = HashSet::new();
+    let mut found: HashSet<String> = HashSet::new();
+
+    for transaction in transactions {
+        if let (Some(action), Some(target)) =
+            (transaction.correctaction, transaction.correctfitid.as_ref())
+        {
+            let before = effective.len();
+            effective.retain(|t| &t.fitid != target);
+
+            if effective.len() < before {
+                found.insert(target.clone());
+            } else if !found.contains(target) {
+                debug!(
+                    "Correction {} targets unseen FITID {target}",
+                    transaction.fitid
+                );
+            }
+            corrected.insert(target.clone());
+
+            if action == CorrectAction::Replace {
+                effective.push(transaction.clone());
+            }
+            continue;
+        }
+
+        if corrected.contains(&transaction.fitid) {
+            debug!(
+                "Dropping {} corrected by an earlier entry",
+                transaction.fitid
+            );
+            found.insert(transaction.fitid.clone());
+            continue;
+        }
+
+        effective.push(transaction.clone());
+    }
+
+    // Targets that were never seen are harmless but usually mean a file is missing
+    for target in corrected.difference(&found) {
+        warn!("Correction target FITID {target} was not found in any statement");
+    }
+
+    effective
+}
+
+impl BankTransactionList {
+    /// The transactions left once every `CORRECTACTION` in the list has been applied.
+    pub fn effective_transactions(&self) -> Vec<Transaction> {
+        apply_corrections(&self.transactions)
+    }
+}
+
+/// Effective transactions per account across several documents, fed in the order given.
+pub fn effective_transactions(documents: &[OfxDocument]) -> BTreeMap<AccountKey, Vec<Transaction>> {
+    let mut by_account: BTreeMap<AccountKey, Vec<&Transaction>> = BTreeMap::new();
+
+    for message in documents.iter().flat_map(|d| &d.body.bank_msgs) {
+        if let Some(list) = &message.stmtrs.banktranlist {
+            by_account
+                .entry(message.stmtrs.bankacctfrom.key())
+                .or_default()
+                .extend(&list.transactions);
+        }
+    }
+
+    by_account
+        .into_iter()
+        .map(|(account, transactions)| (account, apply_corrections(transactions)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{account, document, statement, transaction};
+    use pretty_assertions::assert_eq;
+
+    fn correction(fitid: &str, target: &str, action: CorrectAction, trnamt: f64) -> Transaction {
+        Transaction {
+            correctfitid: Some(target.to_string()),
+            correctaction: Some(action),
+            ..transaction(fitid, (2025, 6, 3), trnamt)
+        }
+    }
+
+    fn fitids(transactions: &[Transaction]) -> Vec<&str> {
+        transactions.iter().map(|t| t.fitid.as_str()).collect()
+    }
+
+    #[test]
+    fn replaces_and_deletes_targets_in_either_order() {
+        let transactions = [
+            correction("r1", "a", CorrectAction::Replace, -12.0),
+            transaction("a", (2025, 6, 1), -10.0),
+            transaction("b", (2025, 6, 2), -20.0),
+            correction("d1", "b", CorrectAction::Delete, 0.0),
+            transaction("c", (2025, 6, 4), -30.0),
+        ];
+
+        assert_eq!(fitids(&apply_corrections(&transactions)), vec!["r1", "c"]);
+    }
+
+    #[test]
+    fn original_repeated_after_its_correction_stays_dropped() {
+        let doc1 = document(vec![statement(
+            "1",
+            vec![
+                transaction("a", (2025, 6, 1), -10.0),
+                transaction("b", (2025, 6, 2), -20.0),
+            ],
+        )]);
+        let doc2_with_correction = document(vec![statement(
+            "1",
+            vec![correction("r1", "a", CorrectAction::Replace, -12.0)],
+        )]);
+        let doc3_repeating_original = document(vec![statement(
+            "1",
+            vec![
+                transaction("a", (2025, 6, 1), -10.0),
+                transaction("c", (2025, 6, 5), -30.0),
+            ],
+        )]);
+
+        let effective =
+            effective_transactions(&[doc1, doc2_with_correction, doc3_repeating_original]);
+
+        assert_eq!(effective.len(), 1);
+        assert_eq!(fitids(&effective[&account("1")]), vec!["b", "r1", "c"]);
+    }
+}
@@ -0,0 +1,95 @@
+use std::cmp::Ordering;
+
+use chrono::NaiveDate;
+
+use crate::domain::{entities::ofx::Transaction, services::categorization::TextMatch};
+
+/// Amounts this close are taken as equal, they only have cents.
+const AMOUNT_EPSILON: f64 = 0.005;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Comparison::Less => ordering.is_lt(),
+            Comparison::LessOrEqual => ordering.is_le(),
+            Comparison::Equal => ordering.is_eq(),
+            Comparison::GreaterOrEqual => ordering.is_ge(),
+            Comparison::Greater => ordering.is_gt(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextField {
+    Name,
+    Memo,
+    TrnType,
+    Fitid,
+}
+
+impl TextField {
+    fn value(self, transaction: &Transaction) -> Option<&str> {
+        match self {
+            TextField::Name => transaction.name.as_deref(),
+            TextField::Memo => transaction.memo.as_deref(),
+            TextField::TrnType => Some(&transaction.trntype),
+            TextField::Fitid => Some(&transaction.fitid),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum FilterTerm {
+    Text(TextField, TextMatch),
+    /// Against `TRNAMT`, so debits are negative.
+    Amount(Comparison, f64),
+    /// Against the posting date, in the zone the date was posted in.
+    Date(Comparison, NaiveDate),
+    /// Matches the name or the memo.
+    Anywhere(TextMatch),
+}
+
+impl FilterTerm {
+    pub fn matches(&self, transaction: &Transaction) -> bool {
+        match self {
+            FilterTerm::Text(field, m) => field.value(transaction).is_some_and(|v| m.matches(v)),
+            FilterTerm::Amount(comparison, amount) => {
+                let difference = transaction.trnamt - amount;
+                let ordering = if difference.abs() < AMOUNT_EPSILON {
+                    Ordering::Equal
+                } else {
+                    difference.total_cmp(&0.0)
+                };
+                comparison.holds(ordering)
+            }
+            FilterTerm::Date(comparison, date) => {
+                comparison.holds(transaction.dtposted.datetime.date_naive().cmp(date))
+            }
+            FilterTerm::Anywhere(m) => [&transaction.name, &transaction.memo]
+                .into_iter()
+                .flatten()
+                .any(|v| m.matches(v)),
+        }
+    }
+}
+
+/// Narrows transactions down to those meeting every one of its terms.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionFilter {
+    pub terms: Vec<FilterTerm>,
+}
+
+impl TransactionFilter {
+    pub fn matches(&self, transaction: &Transaction) -> bool {
+        self.terms.iter().all(|t| t.matches(transaction))
+    }
+}
@@ -0,0 +1,190 @@
+use chrono::{Days, NaiveDate};
+
+use crate::domain::{
+    entities::ofx::{AccountKey, Balance, Transaction},
+    services::{
+        payee::PayeeNormalizer,
+        recurring::{Cadence, RecurringOptions, RecurringSeries, detect_recurring},
+    },
+};
+
+#[derive(Debug, Clone)]
+pub struct ForecastOptions {
+    /// How many days after the balance date to project.
+    pub days: u32,
+    pub recurring: RecurringOptions,
+}
+
+impl Default for ForecastOptions {
+    fn default() -> Self {
+        ForecastOptions {
+            days: 30,
+            recurring: RecurringOptions::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectedTransaction {
+    pub payee: String,
+    /// Signed like `TRNAMT`.
+    pub amount: f64,
+    pub cadence: Cadence,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ForecastDay {
+    pub date: NaiveDate,
+    pub transactions: Vec<ProjectedTransaction>,
+    /// Projected balance at the end of the day.
+    pub balance: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Forecast {
+    /// Date of the balance the forecast starts from.
+    pub start: NaiveDate,
+    pub opening_balance: f64,
+    /// One entry per day after `start`, in order.
+    pub days: Vec<ForecastDay>,
+}
+
+impl Forecast {
+    /// The first day the balance is projected to go below zero.
+    pub fn first_negative(&self) -> Option<&ForecastDay> {
+        self.days.iter().find(|d| d.balance < 0.0)
+    }
+
+    pub fn lowest(&self) -> Option<&ForecastDay> {
+        self.days
+            .iter()
+            .min_by(|a, b| a.balance.total_cmp(&b.balance))
+    }
+}
+
+/// Projects the balance of an account forward from its ledger balance.
+///
+/// Recurring series are detected in `transactions` as of the ledger balance date, so they
+/// should all belong to `account`, the one the balance is for.
+pub fn forecast(
+    account: &AccountKey,
+    ledgerbal: &Balance,
+    transactions: &[Transaction],
+    normalizer: &PayeeNormalizer,
+    options: &ForecastOptions,
+) -> Forecast {
+    let recurring = RecurringOptions {
+        as_of: Some(ledgerbal.dtasof.datetime.date_naive()),
+        ..options.recurring.clone()
+    };
+    let series = detect_recurring(
+        transactions.iter().map(|t| (account, t)),
+        normalizer,
+        &recurring,
+    );
+    project(ledgerbal, &series, options.days)
+}
+
+/// Adds the expected occurrences of each series to the balance, day by day.
+///
+/// A series that is already overdue is taken to have stopped and left out. One that is due
+/// but within its tolerance is projected on the first day.
+pub fn project(ledgerbal: &Balance, series: &[RecurringSeries], days: u32) -> Forecast {
+    let start = ledgerbal.dtasof.datetime.date_naive();
+    let mut forecast = Forecast {
+        start,
+        opening_balance: ledgerbal.balamt,
+        days: (1..=days)
+            .map(|d| ForecastDay {
+                date: start + Days::new(u64::from(d)),
+                transactions: Vec::new(),
+                balance: 0.0,
+            })
+            .collect(),
+    };
+    let Some(end) = forecast.days.last().map(|d| d.date) else {
+        return forecast;
+    };
+
+    let active = series
+        .iter()
+        .filter(|s| s.missed.last().is_none_or(|&missed| missed < s.last_date()));
+    for series in active {
+        let mut periods = 0;
+        let mut date = series.expected_next_date;
+        while date <= end {
+            let day = (date - start).num_days().max(1) as usize - 1;
+            forecast.days[day].transactions.push(ProjectedTransaction {
+                payee: series.payee.clone(),
+                amount: series.expected_amount,
+                cadence: series.cadence,
+            });
+            periods += 1;
+            date = series.cadence.advance(series.expected_next_date, periods);
+        }
+    }
+
+    let mut balance = ledgerbal.balamt;
+    for day in forecast.days.iter_mut() {
+        balance += day.transactions.iter().map(|t| t.amount).sum::<f64>();
+        day.balance = balance;
+    }
+    forecast
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, balance};
+    use pretty_assertions::assert_eq;
+
+    fn transaction(fitid: &str, (m, d): (u32, u32), trnamt: f64, name: &str) -> Transaction {
+        Transaction {
+            name: Some(name.to_string()),
+            ..test_support::transaction(fitid, (2025, m, d), trnamt)
+        }
+    }
+
+    #[test]
+    fn projects_recurring_transactions_and_finds_negative_balance() {
+        let transactions = vec![
+            transaction("1", (3, 5), 3000.0, "ACME PAYROLL"),
+            transaction("2", (4, 5), 3000.0, "ACME PAYROLL"),
+            transaction("3", (5, 5), 3000.0, "ACME PAYROLL"),
+            transaction("4", (3, 1), -2500.0, "LANDLORD"),
+            transaction("5", (4, 1), -2500.0, "LANDLORD"),
+            transaction("6", (5, 1), -2500.0, "LANDLORD"),
+            transaction("7", (2, 20), -15.0, "GYM"),
+            transaction("8", (3, 20), -15.0, "GYM"),
+            transaction("9", (4, 20), -15.0, "GYM"),
+        ];
+        let ledgerbal = balance(1000.0, (2025, 5, 25));
+        let options = ForecastOptions {
+            days: 14,
+            ..Default::default()
+        };
+
+        let forecast = forecast(
+            &test_support::account("1"),
+            &ledgerbal,
+            &transactions,
+            &PayeeNormalizer::default(),
+            &options,
+        );
+
+        assert_eq!(forecast.days.len(), 14);
+        let projected: Vec<(NaiveDate, f64, f64)> = forecast
+            .days
+            .iter()
+            .flat_map(|d| d.transactions.iter().map(|t| (d.date, t.amount, d.balance)))
+            .collect();
+        let date = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+        // The gym series missed May and is left out
+        assert_eq!(
+            projected,
+            vec![(date(6, 1), -2500.0, -1500.0), (date(6, 5), 3000.0, 1500.0)]
+        );
+        assert_eq!(forecast.first_negative().map(|d| d.date), Some(date(6, 1)));
+        assert_eq!(forecast.days.last().unwrap().balance, 1500.0);
+    }
+}
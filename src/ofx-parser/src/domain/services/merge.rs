@@ -0,0 +1,386 @@
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+};
+
+use log::debug;
+
+use crate::domain::{
+    entities::ofx::{AccountKey, Balance, BankAccount, OfxDocument, Transaction},
+    services::corrections::apply_corrections,
+};
+
+#[derive(Debug, Clone)]
+pub struct MergeOptions {
+    /// Match transactions whose `FITID` changed between downloads by date, amount and name.
+    pub fuzzy: bool,
+    /// How many days apart two postings may be and still be the same transaction.
+    pub date_tolerance_days: i64,
+    pub amount_tolerance: f64,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        MergeOptions {
+            fuzzy: true,
+            date_tolerance_days: 0,
+            amount_tolerance: 0.005,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergedAccount {
+    pub account: BankAccount,
+    pub curdef: String,
+    /// Effective transactions in posting order, corrections applied.
+    pub transactions: Vec<Transaction>,
+    /// The most recent balances across all documents.
+    pub ledgerbal: Option<Balance>,
+    pub availbal: Option<Balance>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeReason {
+    SameFitid,
+    /// Different `FITID`s, but the same date, amount and name.
+    Fuzzy,
+}
+
+/// One transaction dropped because another document already had it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeEvent {
+    pub account: AccountKey,
+    pub reason: MergeReason,
+    /// `FITID` of the copy that was kept.
+    pub kept_fitid: String,
+    pub kept_document: usize,
+    pub dropped: Transaction,
+    pub dropped_document: usize,
+}
+
+impl fmt::Display for MergeEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self.reason {
+            MergeReason::SameFitid => "same FITID",
+            MergeReason::Fuzzy => "same date, amount and name",
+        };
+        write!(
+            f,
+            "{}/{}: dropped {} from document {}, duplicate of {} from document {} ({reason})",
+            self.account.bankid,
+            self.account.acctid,
+            self.dropped.fitid,
+            self.dropped_document,
+            self.kept_fitid,
+            self.kept_document,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeResult {
+    pub accounts: Vec<MergedAccount>,
+    pub merged: Vec<MergeEvent>,
+}
+
+/// Combines overlapping downloads into one transaction list per account.
+///
+/// Documents are taken in the order given and the first copy of a transaction wins. Fuzzy
+/// matches are only looked for across documents, since two identical purchases on the same day
+/// within one statement are two purchases, and each earlier transaction absorbs at most one
+/// transaction from any later document.
+pub fn merge_documents(documents: &[OfxDocument], options: &MergeOptions) -> MergeResult {
+    let mut accounts: BTreeMap<AccountKey, AccountMerge> = BTreeMap::new();
+    let mut merged: Vec<MergeEvent> = Vec::new();
+
+    for (document_index, document) in documents.iter().enumerate() {
+        let mut claimed: HashMap<AccountKey, HashSet<usize>> = HashMap::new();
+
+        for stmtrs in document.body.bank_msgs.iter().map(|m| &m.stmtrs) {
+            let key = stmtrs.bankacctfrom.key();
+            let account = accounts.entry(key.clone()).or_insert_with(|| AccountMerge {
+                account: stmtrs.bankacctfrom.clone(),
+                curdef: stmtrs.curdef.clone(),
+                kept: Vec::new(),
+                by_fitid: HashMap::new(),
+                ledgerbal: None,
+                availbal: None,
+            });
+            account.ledgerbal = latest(account.ledgerbal.take(), stmtrs.ledgerbal.as_ref());
+            account.availbal = latest(account.availbal.take(), stmtrs.availbal.as_ref());
+
+            let claimed = claimed.entry(key.clone()).or_default();
+            for transaction in stmtrs.banktranlist.iter().flat_map(|l| &l.transactions) {
+                let duplicate = account
+                    .by_fitid
+                    .get(&transaction.fitid)
+                    .map(|&i| (i, MergeReason::SameFitid))
+                    .or_else(|| {
+                        options
+                            .fuzzy
+                            .then(|| {
+                                account.fuzzy_match(transaction, document_index, claimed, options)
+                            })
+                            .flatten()
+                            .map(|i| (i, MergeReason::Fuzzy))
+                    });
+
+                match duplicate {
+                    Some((index, reason)) => {
+                        claimed.insert(index);
+                        let (kept, kept_document) = &account.kept[index];
+                        debug!(
+                            "Merging {} into {} ({reason:?})",
+                            transaction.fitid, kept.fitid
+                        );
+                        merged.push(MergeEvent {
+                            account: key.clone(),
+                            reason,
+                            kept_fitid: kept.fitid.clone(),
+                            kept_document: *kept_document,
+                            dropped: transaction.clone(),
+                            dropped_document: document_index,
+                        });
+                        // Later downloads may repeat the dropped copy's FITID
+                        account
+                            .by_fitid
+                            .entry(transaction.fitid.clone())
+                            .or_insert(index);
+                    }
+                    None => {
+                        account
+                            .by_fitid
+                            .insert(transaction.fitid.clone(), account.kept.len());
+                        account.kept.push((transaction.clone(), document_index));
+                    }
+                }
+            }
+        }
+    }
+
+    let accounts = accounts
+        .into_values()
+        .map(|a| {
+            let mut transactions = apply_corrections(a.kept.iter().map(|(t, _)| t));
+            transactions.sort_by_key(|t| t.dtposted.datetime);
+            MergedAccount {
+                account: a.account,
+                curdef: a.curdef,
+                transactions,
+                ledgerbal: a.ledgerbal,
+                availbal: a.availbal,
+            }
+        })
+        .collect();
+
+    MergeResult { accounts, merged }
+}
+
+struct AccountMerge {
+    account: BankAccount,
+    curdef: String,
+    /// Transactions kept so far along with the document they came from.
+    kept: Vec<(Transaction, usize)>,
+    by_fitid: HashMap<String, usize>,
+    ledgerbal: Option<Balance>,
+    availbal: Option<Balance>,
+}
+
+impl AccountMerge {
+    fn fuzzy_match(
+        &self,
+        transaction: &Transaction,
+        document_index: usize,
+        claimed: &HashSet<usize>,
+        options: &MergeOptions,
+    ) -> Option<usize> {
+        // Corrections refer to FITIDs, guessing at them would corrupt the result
+        if transaction.correctaction.is_some() {
+            return None;
+        }
+
+        let name = comparable_name(transaction);
+        let day = transaction.dtposted.datetime.date_naive();
+
+        self.kept
+            .iter()
+            .enumerate()
+            .find(|(i, (kept, kept_document))| {
+                *kept_document != document_index
+                    && !claimed.contains(i)
+                    && kept.correctaction.is_none()
+                    && (kept.trnamt - transaction.trnamt).abs() <= options.amount_tolerance
+                    && (kept.dtposted.datetime.date_naive() - day).num_days().abs()
+                        <= options.date_tolerance_days
+                    && comparable_name(kept) == name
+            })
+            .map(|(i, _)| i)
+    }
+}
+
+/// Name (or memo, when the name is missing) reduced to lowercase letters and digits.
+fn comparable_name(transaction: &Transaction) -> String {
+    transaction
+        .name
+        .as_ref()
+        .or(transaction.memo.as_ref())
+        .map_or(String::new(), |n| {
+            n.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect()
+        })
+}
+
+fn latest(current: Option<Balance>, candidate: Option<&Balance>) -> Option<Balance> {
+    match (current, candidate) {
+        (Some(current), Some(candidate)) if candidate.dtasof.datetime > current.dtasof.datetime => {
+            Some(candidate.clone())
+        }
+        (Some(current), _) => Some(current),
+        (None, candidate) => candidate.cloned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::entities::ofx::CorrectAction,
+        test_support::{account, document, statement, transaction},
+    };
+    use pretty_assertions::assert_eq;
+
+    fn purchase(fitid: &str, day: u32, trnamt: f64, name: &str) -> Transaction {
+        Transaction {
+            name: Some(name.to_string()),
+            ..transaction(fitid, (2025, 6, day), trnamt)
+        }
+    }
+
+    fn fitids(result: &MergeResult) -> Vec<&str> {
+        result.accounts[0]
+            .transactions
+            .iter()
+            .map(|t| t.fitid.as_str())
+            .collect()
+    }
+
+    fn reasons(result: &MergeResult) -> Vec<(&str, &str, MergeReason)> {
+        result
+            .merged
+            .iter()
+            .map(|e| (e.dropped.fitid.as_str(), e.kept_fitid.as_str(), e.reason))
+            .collect()
+    }
+
+    #[test]
+    fn drops_repeated_fitids() {
+        let documents = [
+            document(vec![statement(
+                "1",
+                vec![
+                    purchase("a", 1, -10.0, "BAKERY"),
+                    purchase("b", 2, -20.0, "MARKET"),
+                ],
+            )]),
+            document(vec![statement(
+                "1",
+                vec![
+                    purchase("b", 2, -20.0, "MARKET"),
+                    purchase("c", 3, -30.0, "GYM"),
+                ],
+            )]),
+        ];
+
+        let result = merge_documents(&documents, &MergeOptions::default());
+
+        assert_eq!(result.accounts.len(), 1);
+        assert_eq!(result.accounts[0].account.key(), account("1"));
+        assert_eq!(fitids(&result), vec!["a", "b", "c"]);
+        assert_eq!(reasons(&result), vec![("b", "b", MergeReason::SameFitid)]);
+        assert_eq!(
+            (
+                result.merged[0].kept_document,
+                result.merged[0].dropped_document
+            ),
+            (0, 1)
+        );
+    }
+
+    #[test]
+    fn matches_changed_fitids_across_documents() {
+        let documents = [
+            document(vec![statement(
+                "1",
+                vec![purchase("a", 1, -10.0, "Bakery 01")],
+            )]),
+            document(vec![statement(
+                "1",
+                vec![
+                    purchase("x", 1, -10.0, "BAKERY-01"),
+                    purchase("y", 1, -10.0, "BAKERY 01"),
+                ],
+            )]),
+        ];
+
+        let result = merge_documents(&documents, &MergeOptions::default());
+
+        // The kept copy absorbs one later transaction, the second identical one is a new purchase
+        assert_eq!(fitids(&result), vec!["a", "y"]);
+        assert_eq!(reasons(&result), vec![("x", "a", MergeReason::Fuzzy)]);
+
+        let strict = merge_documents(
+            &documents,
+            &MergeOptions {
+                fuzzy: false,
+                ..Default::default()
+            },
+        );
+        assert_eq!(fitids(&strict), vec!["a", "x", "y"]);
+    }
+
+    #[test]
+    fn keeps_identical_purchases_within_one_document() {
+        let documents = [document(vec![statement(
+            "1",
+            vec![
+                purchase("a", 1, -5.0, "COFFEE"),
+                purchase("b", 1, -5.0, "COFFEE"),
+            ],
+        )])];
+
+        let result = merge_documents(&documents, &MergeOptions::default());
+
+        assert_eq!(fitids(&result), vec!["a", "b"]);
+        assert_eq!(result.merged, Vec::new());
+    }
+
+    #[test]
+    fn applies_corrections_from_any_document() {
+        let replacement = Transaction {
+            correctfitid: Some("a".to_string()),
+            correctaction: Some(CorrectAction::Replace),
+            ..purchase("r", 1, -12.0, "BAKERY")
+        };
+        let documents = [
+            document(vec![statement(
+                "1",
+                vec![
+                    purchase("a", 1, -10.0, "BAKERY"),
+                    purchase("b", 2, -20.0, "MARKET"),
+                ],
+            )]),
+            document(vec![statement(
+                "1",
+                vec![purchase("a", 1, -10.0, "BAKERY"), replacement],
+            )]),
+        ];
+
+        let result = merge_documents(&documents, &MergeOptions::default());
+
+        assert_eq!(fitids(&result), vec!["r", "b"]);
+        assert_eq!(reasons(&result), vec![("a", "a", MergeReason::SameFitid)]);
+    }
+}
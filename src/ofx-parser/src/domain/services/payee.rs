@@ -0,0 +1,172 @@
+use crate::domain::{entities::ofx::Transaction, services::categorization::TextMatch};
+
+/// Maps matching raw names to a fixed display payee.
+#[derive(Debug, Clone)]
+pub struct PayeeAlias {
+    pub payee: String,
+    /// Tried against both the raw and the normalized name.
+    pub pattern: TextMatch,
+}
+
+#[derive(Debug, Clone)]
+pub struct PayeeNormalizer {
+    /// Payment processors that put their own name before the merchant's, as in `PAG*Merchant`.
+    pub processors: Vec<String>,
+    /// Company type suffixes dropped from the end of a name.
+    pub legal_suffixes: Vec<String>,
+    /// Checked in order before any normalization.
+    pub aliases: Vec<PayeeAlias>,
+}
+
+impl Default for PayeeNormalizer {
+    fn default() -> Self {
+        let strings = |s: &[&str]| s.iter().map(|s| s.to_string()).collect();
+        PayeeNormalizer {
+            processors: strings(&[
+                "PAG",
+                "PAGSEGURO",
+                "MP",
+                "MERCADOPAGO",
+                "PICPAY",
+                "EBANX",
+                "SQ",
+                "SP",
+                "TST",
+                "PAYPAL",
+                "PP",
+                "STRIPE",
+                "SUMUP",
+            ]),
+            legal_suffixes: strings(&["LTDA", "ME", "EPP", "EIRELI", "SA", "INC", "LLC", "LTD"]),
+            aliases: Vec::new(),
+        }
+    }
+}
+
+impl PayeeNormalizer {
+    pub fn with_aliases(aliases: Vec<PayeeAlias>) -> Self {
+        PayeeNormalizer {
+            aliases,
+            ..Default::default()
+        }
+    }
+
+    /// Display payee for a transaction, from its name or, when that is missing, its memo.
+    pub fn payee(&self, transaction: &Transaction) -> String {
+        let raw = transaction
+            .name
+            .as_deref()
+            .or(transaction.memo.as_deref())
+            .unwrap_or_default();
+        self.display_name(raw)
+    }
+
+    pub fn display_name(&self, raw: &str) -> String {
+        let normalized = self.normalize(raw);
+        self.aliases
+            .iter()
+            .find(|a| a.pattern.matches(raw) || a.pattern.matches(&normalized))
+            .map_or(normalized, |a| a.payee.clone())
+    }
+
+    /// Strips processor prefixes, trailing reference numbers, location codes and company type
+    /// suffixes, then title-cases what is left.
+    ///
+    /// `PAG*JoseDaSilva 123` becomes `Jose Da Silva`, `UBER *TRIP 8XZ1` becomes `Uber` and
+    /// `SUPERMERCADO X LTDA 0042` becomes `Supermercado X`.
+    pub fn normalize(&self, raw: &str) -> String {
+        let raw = raw.trim();
+        // Either `PROCESSOR*merchant` or `MERCHANT*descriptor`
+        let merchant = match raw.split_once('*') {
+            Some((left, right)) if left.trim().is_empty() || self.is_processor(left) => right,
+            Some((left, _)) => left,
+            None => raw,
+        };
+
+        let mut tokens: Vec<&str> = merchant.split_whitespace().collect();
+        while tokens.len() > 1 && tokens.last().is_some_and(|t| self.is_noise(t)) {
+            tokens.pop();
+        }
+
+        // `ACME, INC` leaves `ACME,` behind
+        if let Some(last) = tokens.last_mut() {
+            *last = last.trim_end_matches([',', '-', '.', '/']);
+        }
+
+        let normalized = tokens
+            .iter()
+            .flat_map(|t| split_camel_case(t))
+            .map(|w| title_case(&w))
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if normalized.is_empty() {
+            raw.to_string()
+        } else {
+            normalized
+        }
+    }
+
+    fn is_processor(&self, prefix: &str) -> bool {
+        let prefix = prefix.trim();
+        self.processors
+            .iter()
+            .any(|p| p.eq_ignore_ascii_case(prefix))
+    }
+
+    fn is_noise(&self, token: &str) -> bool {
+        let is_reference = token.starts_with('#') || token.chars().any(|c| c.is_ascii_digit());
+        // State and country codes such as `SP` or `BR`
+        let is_location = token.len() == 2 && token.chars().all(|c| c.is_ascii_uppercase());
+        let is_legal = self
+            .legal_suffixes
+            .iter()
+            .any(|s| s.eq_ignore_ascii_case(token.trim_end_matches('.')));
+
+        is_reference || is_location || is_legal
+    }
+}
+
+/// `JoseDaSilva` into `Jose`, `Da`, `Silva`. All-caps tokens are left whole.
+fn split_camel_case(token: &str) -> Vec<String> {
+    let mut words = vec![String::new()];
+    let mut previous_lower = false;
+    for c in token.chars() {
+        if c.is_uppercase() && previous_lower {
+            words.push(String::new());
+        }
+        previous_lower = c.is_lowercase();
+        words.last_mut().unwrap().push(c);
+    }
+    words
+}
+
+fn title_case(word: &str) -> String {
+    let mut chars = word.chars();
+    chars.next().map_or(String::new(), |first| {
+        first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("PAG*JoseDaSilva 123", "Jose Da Silva")]
+    #[case("UBER *TRIP 8XZ1", "Uber")]
+    #[case("SUPERMERCADO X LTDA 0042", "Supermercado X")]
+    #[case("PAYPAL *SPOTIFY", "Spotify")]
+    #[case("NETFLIX.COM SAO PAULO BR", "Netflix.com Sao Paulo")]
+    #[case("POSTO SHELL 221", "Posto Shell")]
+    #[case("ACME, INC", "Acme")]
+    #[case("12345", "12345")]
+    fn normalizes_payee(#[case] raw: &str, #[case] expected: &str) {
+        assert_eq!(PayeeNormalizer::default().normalize(raw), expected);
+    }
+}
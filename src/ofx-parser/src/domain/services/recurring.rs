@@ -0,0 +1,396 @@
+use std::{collections::BTreeMap, fmt};
+
+use chrono::{Days, Months, NaiveDate};
+
+use crate::domain::{
+    entities::ofx::{AccountKey, Transaction},
+    services::payee::PayeeNormalizer,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Cadence {
+    Weekly,
+    Monthly,
+    Annual,
+}
+
+impl Cadence {
+    const ALL: [Cadence; 3] = [Cadence::Weekly, Cadence::Monthly, Cadence::Annual];
+
+    /// Average length of one period in days.
+    fn days(self) -> f64 {
+        match self {
+            Cadence::Weekly => 7.0,
+            Cadence::Monthly => 30.44,
+            Cadence::Annual => 365.25,
+        }
+    }
+
+    /// How far an occurrence may drift from its expected date, in days.
+    fn tolerance(self) -> f64 {
+        match self {
+            Cadence::Weekly => 1.0,
+            Cadence::Monthly => 4.0,
+            Cadence::Annual => 10.0,
+        }
+    }
+
+    /// The date `periods` periods after `date`, clamped to the end of shorter months.
+    pub fn advance(self, date: NaiveDate, periods: u32) -> NaiveDate {
+        match self {
+            Cadence::Weekly => date + Days::new(7 * u64::from(periods)),
+            Cadence::Monthly => date + Months::new(periods),
+            Cadence::Annual => date + Months::new(12 * periods),
+        }
+    }
+
+    /// Number of whole periods `days` spans, if it is close enough to a whole number of them.
+    fn periods(self, days: i64) -> Option<u32> {
+        let periods = (days as f64 / self.days()).round();
+        (periods >= 1.0
+            && (days as f64 - periods * self.days()).abs() <= self.tolerance() * periods)
+            .then_some(periods as u32)
+    }
+}
+
+impl fmt::Display for Cadence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Cadence::Weekly => "weekly",
+            Cadence::Monthly => "monthly",
+            Cadence::Annual => "annual",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceChange {
+    pub date: NaiveDate,
+    pub fitid: String,
+    pub previous: f64,
+    pub amount: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecurringSeries {
+    /// Account the series is posted to.
+    pub account: AccountKey,
+    pub payee: String,
+    pub cadence: Cadence,
+    /// In posting order.
+    pub occurrences: Vec<Transaction>,
+    pub expected_next_date: NaiveDate,
+    /// The amount of the latest occurrence.
+    pub expected_amount: f64,
+    /// Dates where an occurrence was expected but none was posted, including any overdue ones.
+    pub missed: Vec<NaiveDate>,
+    pub price_changes: Vec<PriceChange>,
+}
+
+impl RecurringSeries {
+    pub fn last_date(&self) -> NaiveDate {
+        self.occurrences
+            .last()
+            .map(|t| t.dtposted.datetime.date_naive())
+            .unwrap_or(self.expected_next_date)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RecurringOptions {
+    /// Largest change between consecutive amounts, relative to the earlier one, for them to
+    /// still count as the same charge.
+    pub amount_tolerance: f64,
+    /// Fewest occurrences for weekly and monthly series. Annual ones need two.
+    pub min_occurrences: usize,
+    /// Date the series are checked against for overdue occurrences. Defaults to the latest
+    /// posting date of each account.
+    pub as_of: Option<NaiveDate>,
+}
+
+impl Default for RecurringOptions {
+    fn default() -> Self {
+        RecurringOptions {
+            amount_tolerance: 0.25,
+            min_occurrences: 3,
+            as_of: None,
+        }
+    }
+}
+
+/// Finds charges and credits that repeat on a weekly, monthly or annual cadence.
+///
+/// Transactions are grouped by account, normalized payee and direction, then each group is split into
+/// candidate series: a posting joins the series whose latest posting is a whole number of
+/// periods before it with an amount within `amount_tolerance`, so one-off charges from the same
+/// payee are left out instead of breaking the cadence. Gaps of more than one period are reported
+/// as missed occurrences and amount differences as price changes. Series are returned by account,
+/// then by payee.
+pub fn detect_recurring<'a>(
+    transactions: impl IntoIterator<Item = (&'a AccountKey, &'a Transaction)>,
+    normalizer: &PayeeNormalizer,
+    options: &RecurringOptions,
+) -> Vec<RecurringSeries> {
+    let mut groups: BTreeMap<(&AccountKey, String, bool), Vec<&Transaction>> = BTreeMap::new();
+    let mut latest: BTreeMap<&AccountKey, NaiveDate> = BTreeMap::new();
+    for (account, transaction) in transactions {
+        let date = transaction.dtposted.datetime.date_naive();
+        latest
+            .entry(account)
+            .and_modify(|d| *d = (*d).max(date))
+            .or_insert(date);
+        if transaction.trnamt != 0.0 {
+            groups
+                .entry((
+                    account,
+                    normalizer.payee(transaction),
+                    transaction.trnamt < 0.0,
+                ))
+                .or_default()
+                .push(transaction);
+        }
+    }
+
+    let mut found = Vec::new();
+    for ((account, payee, _), mut occurrences) in groups {
+        let as_of = options.as_of.unwrap_or(latest[account]);
+        occurrences.sort_by_key(|t| t.dtposted.datetime);
+        let Some((cadence, candidates)) = Cadence::ALL
+            .into_iter()
+            .map(|cadence| (cadence, candidate_series(&occurrences, cadence, options)))
+            .find(|(_, candidates)| !candidates.is_empty())
+        else {
+            continue;
+        };
+        found.extend(candidates.iter().filter_map(|(occurrences, gaps)| {
+            series(account, payee.clone(), cadence, occurrences, gaps, as_of)
+        }));
+    }
+    found
+}
+
+/// Splits postings sorted by date into the series they fit at the given cadence, along with the
+/// number of periods between consecutive postings of each, keeping only plausible ones.
+fn candidate_series<'a>(
+    occurrences: &[&'a Transaction],
+    cadence: Cadence,
+    options: &RecurringOptions,
+) -> Vec<(Vec<&'a Transaction>, Vec<u32>)> {
+    let mut candidates: Vec<(Vec<&Transaction>, Vec<u32>)> = Vec::new();
+
+    for &transaction in occurrences {
+        let date = transaction.dtposted.datetime.date_naive();
+        let fit = candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (series, _))| {
+                let last = series.last()?;
+                let similar = (transaction.trnamt - last.trnamt).abs()
+                    <= last.trnamt.abs() * options.amount_tolerance;
+                let days = (date - last.dtposted.datetime.date_naive()).num_days();
+                similar
+                    .then(|| cadence.periods(days))
+                    .flatten()
+                    .map(|p| (i, p))
+            })
+            .min_by_key(|&(_, periods)| periods);
+
+        match fit {
+            Some((i, periods)) => {
+                candidates[i].0.push(transaction);
+                candidates[i].1.push(periods);
+            }
+            None => candidates.push((vec![transaction], Vec::new())),
+        }
+    }
+
+    let min_occurrences = match cadence {
+        Cadence::Annual => 2,
+        _ => options.min_occurrences,
+    };
+    candidates.retain(|(series, gaps)| {
+        // Mostly on schedule, otherwise any sparse set of postings would fit a short cadence
+        let on_schedule = gaps.iter().filter(|&&g| g == 1).count();
+        series.len() >= min_occurrences && on_schedule * 2 >= gaps.len()
+    });
+    candidates
+}
+
+fn series(
+    account: &AccountKey,
+    payee: String,
+    cadence: Cadence,
+    occurrences: &[&Transaction],
+    gaps: &[u32],
+    as_of: NaiveDate,
+) -> Option<RecurringSeries> {
+    let dates: Vec<NaiveDate> = occurrences
+        .iter()
+        .map(|t| t.dtposted.datetime.date_naive())
+        .collect();
+
+    let mut missed: Vec<NaiveDate> = dates
+        .iter()
+        .zip(gaps)
+        .flat_map(|(&date, &periods)| (1..periods).map(move |p| cadence.advance(date, p)))
+        .collect();
+
+    let last = *dates.last()?;
+    let mut periods = 1;
+    let mut expected_next_date = cadence.advance(last, periods);
+    while (as_of - expected_next_date).num_days() as f64 > cadence.tolerance() {
+        missed.push(expected_next_date);
+        periods += 1;
+        expected_next_date = cadence.advance(last, periods);
+    }
+
+    let price_changes = occurrences
+        .windows(2)
+        .filter(|w| (w[1].trnamt - w[0].trnamt).abs() > 0.005)
+        .map(|w| PriceChange {
+            date: w[1].dtposted.datetime.date_naive(),
+            fitid: w[1].fitid.clone(),
+            previous: w[0].trnamt,
+            amount: w[1].trnamt,
+        })
+        .collect();
+
+    Some(RecurringSeries {
+        account: account.clone(),
+        payee,
+        cadence,
+        occurrences: occurrences.iter().map(|&t| t.clone()).collect(),
+        expected_next_date,
+        expected_amount: occurrences.last()?.trnamt,
+        missed,
+        price_changes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use pretty_assertions::assert_eq;
+
+    fn transaction(
+        fitid: &str,
+        (y, m, d): (i32, u32, u32),
+        trnamt: f64,
+        name: &str,
+    ) -> Transaction {
+        Transaction {
+            name: Some(name.to_string()),
+            ..test_support::transaction(fitid, (y, m, d), trnamt)
+        }
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn detects_monthly_series_with_missed_and_changed_occurrences() {
+        let transactions = [
+            transaction("1", (2025, 1, 10), -39.90, "NETFLIX.COM 001"),
+            transaction("2", (2025, 2, 11), -39.90, "NETFLIX.COM 002"),
+            transaction("3", (2025, 3, 10), -39.90, "NETFLIX.COM 003"),
+            transaction("4", (2025, 5, 9), -44.90, "NETFLIX.COM 004"),
+            transaction("5", (2025, 2, 3), -120.00, "BAKERY"),
+            transaction("6", (2025, 2, 20), -15.00, "BAKERY"),
+            transaction("7", (2025, 3, 1), -80.00, "BAKERY"),
+        ];
+        let account = test_support::account("1");
+        let options = RecurringOptions {
+            as_of: Some(date(2025, 6, 20)),
+            ..Default::default()
+        };
+
+        let series = detect_recurring(
+            transactions.iter().map(|t| (&account, t)),
+            &PayeeNormalizer::default(),
+            &options,
+        );
+
+        assert_eq!(series.len(), 1);
+        let netflix = &series[0];
+        assert_eq!(netflix.payee, "Netflix.com");
+        assert_eq!(netflix.cadence, Cadence::Monthly);
+        assert_eq!(netflix.expected_amount, -44.90);
+        assert_eq!(netflix.missed, vec![date(2025, 4, 10), date(2025, 6, 9)]);
+        assert_eq!(netflix.expected_next_date, date(2025, 7, 9));
+        assert_eq!(netflix.price_changes.len(), 1);
+        assert_eq!(netflix.price_changes[0].fitid, "4");
+    }
+
+    #[test]
+    fn leaves_out_charges_off_the_cadence() {
+        let transactions = [
+            transaction("1", (2025, 1, 10), -39.90, "SPOTIFY"),
+            transaction("2", (2025, 2, 10), -39.90, "SPOTIFY"),
+            transaction("gift", (2025, 2, 24), -39.90, "SPOTIFY"),
+            transaction("3", (2025, 3, 10), -39.90, "SPOTIFY"),
+            transaction("4", (2025, 4, 10), -39.90, "SPOTIFY"),
+        ];
+        let account = test_support::account("1");
+        let options = RecurringOptions {
+            as_of: Some(date(2025, 4, 20)),
+            ..Default::default()
+        };
+
+        let series = detect_recurring(
+            transactions.iter().map(|t| (&account, t)),
+            &PayeeNormalizer::default(),
+            &options,
+        );
+
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].cadence, Cadence::Monthly);
+        assert_eq!(
+            series[0]
+                .occurrences
+                .iter()
+                .map(|t| t.fitid.as_str())
+                .collect::<Vec<_>>(),
+            vec!["1", "2", "3", "4"]
+        );
+        assert_eq!(series[0].missed, Vec::new());
+        assert_eq!(series[0].expected_next_date, date(2025, 5, 10));
+    }
+
+    #[test]
+    fn keeps_each_account_apart() {
+        let (checking, card) = (test_support::account("1"), test_support::account("2"));
+        let transactions = [
+            (&card, transaction("c1", (2025, 1, 5), -29.90, "SPOTIFY")),
+            (&card, transaction("c2", (2025, 2, 5), -29.90, "SPOTIFY")),
+            (&card, transaction("c3", (2025, 3, 5), -29.90, "SPOTIFY")),
+            (
+                &checking,
+                transaction("t1", (2025, 1, 20), -500.0, "JOHN DOE"),
+            ),
+            (&card, transaction("t2", (2025, 2, 20), 500.0, "JOHN DOE")),
+            (
+                &checking,
+                transaction("t3", (2025, 3, 20), -500.0, "JOHN DOE"),
+            ),
+            (&card, transaction("t4", (2025, 4, 20), -500.0, "JOHN DOE")),
+        ];
+
+        let series = detect_recurring(
+            transactions.iter().map(|(a, t)| (*a, t)),
+            &PayeeNormalizer::default(),
+            &RecurringOptions::default(),
+        );
+
+        assert_eq!(
+            series
+                .iter()
+                .map(|s| (s.account.acctid.as_str(), s.payee.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("2", "Spotify")]
+        );
+        assert_eq!(series[0].expected_next_date, date(2025, 5, 5));
+        assert_eq!(series[0].missed, vec![date(2025, 4, 5)]);
+    }
+}
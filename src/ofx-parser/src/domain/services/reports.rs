@@ -0,0 +1,300 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+};
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::domain::{
+    entities::{
+        annotation::Annotations,
+        ofx::{AccountKey, OfxDocument, Transaction, TransactionRef},
+    },
+    services::{
+        categorization::Categorizer,
+        merge::{MergeOptions, merge_documents},
+        payee::PayeeNormalizer,
+        transfers::{TransferOptions, match_transfers, transfer_refs},
+    },
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Period {
+    #[default]
+    Month,
+    Quarter,
+    Year,
+}
+
+impl Period {
+    /// First day of the period `date` falls in.
+    pub fn start(self, date: NaiveDate) -> NaiveDate {
+        let month = match self {
+            Period::Month => date.month(),
+            Period::Quarter => (date.month0() / 3) * 3 + 1,
+            Period::Year => 1,
+        };
+        NaiveDate::from_ymd_opt(date.year(), month, 1).unwrap_or(date)
+    }
+
+    /// `2025-03`, `2025-Q1` or `2025`.
+    pub fn label(self, start: NaiveDate) -> String {
+        match self {
+            Period::Month => start.format("%Y-%m").to_string(),
+            Period::Quarter => format!("{}-Q{}", start.year(), start.month0() / 3 + 1),
+            Period::Year => start.year().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Period::Month => "monthly",
+            Period::Quarter => "quarterly",
+            Period::Year => "yearly",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GroupBy {
+    #[default]
+    TransactionType,
+    Payee,
+    Category,
+}
+
+impl fmt::Display for GroupBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            GroupBy::TransactionType => "type",
+            GroupBy::Payee => "payee",
+            GroupBy::Category => "category",
+        })
+    }
+}
+
+/// Income and expenses of one period or group. Expenses are kept as a positive amount.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Totals {
+    pub income: f64,
+    pub expense: f64,
+    pub count: usize,
+}
+
+impl Totals {
+    pub fn net(&self) -> f64 {
+        self.income - self.expense
+    }
+
+    fn add(&mut self, amount: f64) {
+        if amount >= 0.0 {
+            self.income += amount;
+        } else {
+            self.expense -= amount;
+        }
+        self.count += 1;
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeriodSummary {
+    pub label: String,
+    pub start: NaiveDate,
+    pub totals: Totals,
+    pub groups: BTreeMap<String, Totals>,
+    /// Money moved between the accounts, left out of `totals` and `groups`.
+    pub transfers: Totals,
+}
+
+/// How one group did over the whole report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupTrend {
+    pub group: String,
+    /// Average net amount per period, counting periods where the group had nothing as zero.
+    pub average: f64,
+    /// Net amount in the latest period.
+    pub latest: f64,
+    /// Latest against the average of the periods before it, as a fraction. `None` when there is
+    /// no earlier period or its average is zero.
+    pub change: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub period: Period,
+    pub group_by: GroupBy,
+    /// Oldest first.
+    pub periods: Vec<PeriodSummary>,
+    pub trends: Vec<GroupTrend>,
+}
+
+impl Report {
+    /// Average income, expense and net per period.
+    pub fn averages(&self) -> (f64, f64, f64) {
+        if self.periods.is_empty() {
+            return (0.0, 0.0, 0.0);
+        }
+        let n = self.periods.len() as f64;
+        let sum = |f: fn(&Totals) -> f64| self.periods.iter().map(|p| f(&p.totals)).sum::<f64>();
+        (
+            sum(|t| t.income) / n,
+            sum(|t| t.expense) / n,
+            sum(Totals::net) / n,
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReportOptions {
+    pub period: Period,
+    pub group_by: GroupBy,
+    pub normalizer: PayeeNormalizer,
+    /// Used when grouping by category. Uncategorized transactions are grouped together.
+    pub categorizer: Categorizer,
+    /// Transfers found with these options are kept out of income and expense. `None` counts
+    /// them like any other transaction.
+    pub transfers: Option<TransferOptions>,
+    /// Split transactions are divided between their split categories when grouping by category.
+    pub annotations: Annotations,
+}
+
+impl Default for ReportOptions {
+    fn default() -> Self {
+        ReportOptions {
+            period: Period::default(),
+            group_by: GroupBy::default(),
+            normalizer: PayeeNormalizer::default(),
+            categorizer: Categorizer::default(),
+            transfers: Some(TransferOptions::default()),
+            annotations: Annotations::default(),
+        }
+    }
+}
+
+pub const UNCATEGORIZED: &str = "Uncategorized";
+
+/// Merges the documents, dropping transactions repeated across overlapping downloads, and
+/// summarizes what is left.
+pub fn summarize_documents(documents: &[OfxDocument], options: &ReportOptions) -> Report {
+    let merged = merge_documents(documents, &MergeOptions::default());
+    let keys: Vec<AccountKey> = merged.accounts.iter().map(|a| a.account.key()).collect();
+    summarize(
+        merged
+            .accounts
+            .iter()
+            .zip(&keys)
+            .flat_map(|(a, key)| a.transactions.iter().map(move |t| (key, t))),
+        options,
+    )
+}
+
+pub fn summarize<'a>(
+    transactions: impl IntoIterator<Item = (&'a AccountKey, &'a Transaction)>,
+    options: &ReportOptions,
+) -> Report {
+    let transactions: Vec<(&AccountKey, &Transaction)> = transactions.into_iter().collect();
+    let transfers = options.transfers.as_ref().map_or(HashSet::new(), |o| {
+        transfer_refs(&match_transfers(transactions.iter().copied(), o))
+    });
+    let mut periods: BTreeMap<NaiveDate, PeriodSummary> = BTreeMap::new();
+
+    for (account, transaction) in transactions {
+        let start = options
+            .period
+            .start(transaction.dtposted.datetime.date_naive());
+        let summary = periods.entry(start).or_insert_with(|| PeriodSummary {
+            label: options.period.label(start),
+            start,
+            totals: Totals::default(),
+            groups: BTreeMap::new(),
+            transfers: Totals::default(),
+        });
+
+        let reference = TransactionRef {
+            account: account.clone(),
+            fitid: transaction.fitid.clone(),
+        };
+        if transfers.contains(&reference) {
+            summary.transfers.add(transaction.trnamt);
+            continue;
+        }
+
+        summary.totals.add(transaction.trnamt);
+
+        let splits = options
+            .annotations
+            .get(&reference)
+            .map_or(&[][..], |a| a.splits.as_slice());
+        if options.group_by == GroupBy::Category && !splits.is_empty() {
+            for split in splits {
+                summary
+                    .groups
+                    .entry(split.category.clone())
+                    .or_default()
+                    .add(split.amount);
+            }
+            continue;
+        }
+
+        let group = match options.group_by {
+            GroupBy::TransactionType => transaction.trntype.clone(),
+            GroupBy::Payee => options.normalizer.payee(transaction),
+            GroupBy::Category => options
+                .categorizer
+                .categorize(transaction, Some(account))
+                .map_or(UNCATEGORIZED.to_string(), |c| c.category),
+        };
+        summary
+            .groups
+            .entry(group)
+            .or_default()
+            .add(transaction.trnamt);
+    }
+
+    let periods: Vec<PeriodSummary> = periods.into_values().collect();
+    let trends = trends(&periods);
+
+    Report {
+        period: options.period,
+        group_by: options.group_by,
+        periods,
+        trends,
+    }
+}
+
+fn trends(periods: &[PeriodSummary]) -> Vec<GroupTrend> {
+    let Some((latest, earlier)) = periods.split_last() else {
+        return Vec::new();
+    };
+
+    let mut groups: BTreeMap<&str, f64> = BTreeMap::new();
+    for summary in periods {
+        for (group, totals) in &summary.groups {
+            *groups.entry(group).or_default() += totals.net();
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|(group, total)| {
+            let latest = latest.groups.get(group).map_or(0.0, Totals::net);
+            let before = (!earlier.is_empty()).then(|| {
+                earlier
+                    .iter()
+                    .filter_map(|p| p.groups.get(group))
+                    .map(Totals::net)
+                    .sum::<f64>()
+                    / earlier.len() as f64
+            });
+
+            GroupTrend {
+                group: group.to_string(),
+                average: total / periods.len() as f64,
+                latest,
+                change: before.filter(|b| *b != 0.0).map(|b| (latest - b) / b.abs()),
+            }
+        })
+        .collect()
+}
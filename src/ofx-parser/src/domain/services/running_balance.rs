@@ -0,0 +1,133 @@
+use crate::domain::{
+    entities::ofx::{Balance, StatementResponse, Transaction},
+    services::corrections::apply_corrections,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalancedTransaction {
+    pub transaction: Transaction,
+    /// Account balance right after this transaction was posted.
+    pub balance: f64,
+}
+
+/// Annotates transactions with the running balance, anchored on a known balance.
+///
+/// Transactions are returned in posting order, ties kept in file order. Those posted up to
+/// `DTASOF` are walked backwards from the anchor, any posted later are walked forwards from it.
+pub fn running_balances(
+    transactions: &[Transaction],
+    anchor: &Balance,
+) -> Vec<BalancedTransaction> {
+    let mut ordered: Vec<&Transaction> = transactions.iter().collect();
+    ordered.sort_by_key(|t| t.dtposted.datetime);
+
+    let settled = ordered
+        .iter()
+        .take_while(|t| t.dtposted.datetime <= anchor.dtasof.datetime)
+        .count();
+    let mut balances = vec![0.0; ordered.len()];
+
+    let mut balance = anchor.balamt;
+    for i in (0..settled).rev() {
+        balances[i] = balance;
+        balance -= ordered[i].trnamt;
+    }
+
+    let mut balance = anchor.balamt;
+    for i in settled..ordered.len() {
+        balance += ordered[i].trnamt;
+        balances[i] = balance;
+    }
+
+    ordered
+        .into_iter()
+        .zip(balances)
+        .map(|(transaction, balance)| BalancedTransaction {
+            transaction: transaction.clone(),
+            balance,
+        })
+        .collect()
+}
+
+/// A statement's effective transactions with their running balance, anchored on `LEDGERBAL`.
+/// Statements without a ledger balance have nothing to anchor on.
+pub fn statement_running_balances(
+    statement: &StatementResponse,
+) -> Option<Vec<BalancedTransaction>> {
+    let anchor = statement.ledgerbal.as_ref()?;
+    let transactions = statement
+        .banktranlist
+        .as_ref()
+        .map_or(Vec::new(), |l| apply_corrections(&l.transactions));
+
+    Some(running_balances(&transactions, anchor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{balance, datetime_at, statement, transaction};
+    use pretty_assertions::assert_eq;
+
+    fn balances(balanced: &[BalancedTransaction]) -> Vec<(&str, f64)> {
+        balanced
+            .iter()
+            .map(|b| (b.transaction.fitid.as_str(), b.balance))
+            .collect()
+    }
+
+    #[test]
+    fn walks_back_before_the_anchor_and_forward_after_it() {
+        let transactions = [
+            transaction("after", (2025, 6, 20), -30.0),
+            transaction("first", (2025, 6, 1), -10.0),
+            transaction("second", (2025, 6, 5), 50.0),
+        ];
+
+        let balanced = running_balances(&transactions, &balance(100.0, (2025, 6, 10)));
+
+        assert_eq!(
+            balances(&balanced),
+            vec![("first", 50.0), ("second", 100.0), ("after", 70.0)]
+        );
+    }
+
+    #[test]
+    fn counts_transactions_posted_at_the_anchor_time_as_settled() {
+        let transactions = [
+            transaction("same", (2025, 6, 10), -10.0),
+            transaction("also same", (2025, 6, 10), -5.0),
+            transaction("later", (2025, 6, 11), -1.0),
+        ];
+
+        let balanced = running_balances(&transactions, &balance(100.0, (2025, 6, 10)));
+
+        assert_eq!(
+            balances(&balanced),
+            vec![("same", 105.0), ("also same", 100.0), ("later", 99.0)]
+        );
+
+        let just_before = running_balances(
+            &transactions,
+            &Balance {
+                dtasof: datetime_at((2025, 6, 10), 11, 59),
+                ..balance(100.0, (2025, 6, 10))
+            },
+        );
+        assert_eq!(
+            balances(&just_before),
+            vec![("same", 90.0), ("also same", 85.0), ("later", 84.0)]
+        );
+    }
+
+    #[test]
+    fn statement_without_ledger_balance_has_no_running_balance() {
+        let mut statement = statement("1", vec![transaction("1", (2025, 6, 1), -10.0)]).stmtrs;
+
+        assert_eq!(statement_running_balances(&statement), None);
+
+        statement.ledgerbal = Some(balance(90.0, (2025, 6, 30)));
+        let balanced = statement_running_balances(&statement).unwrap();
+        assert_eq!(balances(&balanced), vec![("1", 90.0)]);
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::HashSet;
+
+use crate::domain::{
+    entities::ofx::{AccountKey, Transaction, TransactionRef},
+    services::categorization::TextMatch,
+};
+
+#[derive(Debug, Clone)]
+pub struct TransferOptions {
+    /// How many days apart the two sides may post.
+    pub date_window_days: i64,
+    pub amount_tolerance: f64,
+    /// `TRNTYPE`s that mark a transaction as a transfer.
+    pub transfer_types: Vec<String>,
+    /// Tried against the name and memo of transactions whose type does not say transfer.
+    pub hints: Vec<TextMatch>,
+}
+
+impl Default for TransferOptions {
+    fn default() -> Self {
+        TransferOptions {
+            date_window_days: 3,
+            amount_tolerance: 0.005,
+            // Paying a credit card off is a transfer to the card account
+            transfer_types: ["XFER", "PAYMENT"].map(str::to_string).to_vec(),
+            hints: ["transf", "xfer", "pagamento", "payment"]
+                .into_iter()
+                .map(|h| TextMatch::Contains(h.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl TransferOptions {
+    fn is_transfer_type(&self, transaction: &Transaction) -> bool {
+        self.transfer_types
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(transaction.trntype.trim()))
+    }
+
+    fn looks_like_transfer(&self, transaction: &Transaction) -> bool {
+        self.is_transfer_type(transaction)
+            || [&transaction.name, &transaction.memo]
+                .into_iter()
+                .flatten()
+                .any(|text| self.hints.iter().any(|h| h.matches(text)))
+    }
+}
+
+/// Money leaving one account and arriving in another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transfer {
+    /// The debit side.
+    pub from: TransactionRef,
+    /// The credit side.
+    pub to: TransactionRef,
+    pub amount: f64,
+    pub days_apart: i64,
+}
+
+/// Pairs debits with credits of the same amount in other accounts.
+///
+/// At least one side of a pair has to look like a transfer, by its type or a hint in its name
+/// or memo, so a salary and a purchase of the same amount are not paired. Debits are taken
+/// oldest first and each one is paired with the closest unpaired credit in the date window,
+/// preferring credits of a transfer type when two are equally close.
+pub fn match_transfers<'a>(
+    transactions: impl IntoIterator<Item = (&'a AccountKey, &'a Transaction)>,
+    options: &TransferOptions,
+) -> Vec<Transfer> {
+    let (mut debits, credits): (Vec<_>, Vec<_>) = transactions
+        .into_iter()
+        .filter(|(_, t)| t.trnamt != 0.0)
+        .partition(|(_, t)| t.trnamt < 0.0);
+    debits.sort_by_key(|(_, t)| t.dtposted.datetime);
+
+    let mut paired: HashSet<usize> = HashSet::new();
+    let mut transfers = Vec::new();
+
+    for (debit_account, debit) in debits {
+        let day = debit.dtposted.datetime.date_naive();
+        let candidate = credits
+            .iter()
+            .enumerate()
+            .filter(|(i, (account, credit))| {
+                !paired.contains(i)
+                    && *account != debit_account
+                    && (credit.trnamt + debit.trnamt).abs() <= options.amount_tolerance
+                    && (options.looks_like_transfer(debit) || options.looks_like_transfer(credit))
+            })
+            .map(|(i, (account, credit))| {
+                let days = (credit.dtposted.datetime.date_naive() - day).num_days();
+                (i, account, credit, days)
+            })
+            .filter(|(.., days)| days.abs() <= options.date_window_days)
+            .min_by_key(|(_, _, credit, days)| (days.abs(), !options.is_transfer_type(credit)));
+
+        if let Some((index, credit_account, credit, days)) = candidate {
+            paired.insert(index);
+            transfers.push(Transfer {
+                from: TransactionRef {
+                    account: debit_account.clone(),
+                    fitid: debit.fitid.clone(),
+                },
+                to: TransactionRef {
+                    account: (*credit_account).clone(),
+                    fitid: credit.fitid.clone(),
+                },
+                amount: credit.trnamt,
+                days_apart: days.abs(),
+            });
+        }
+    }
+
+    transfers
+}
+
+/// Both sides of every transfer, for quick lookups.
+pub fn transfer_refs(transfers: &[Transfer]) -> HashSet<TransactionRef> {
+    transfers
+        .iter()
+        .flat_map(|t| [t.from.clone(), t.to.clone()])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{self, account};
+    use pretty_assertions::assert_eq;
+
+    fn transaction(fitid: &str, day: u32, trnamt: f64) -> Transaction {
+        Transaction {
+            trntype: "XFER".to_string(),
+            ..test_support::transaction(fitid, (2025, 6, day), trnamt)
+        }
+    }
+
+    #[test]
+    fn pairs_closest_credit_in_another_account() {
+        let (checking, savings) = (account("1"), account("2"));
+        let transactions = [
+            (&checking, transaction("c1", 10, -500.0)),
+            (&checking, transaction("c2", 11, 500.0)),
+            (&savings, transaction("s1", 13, 500.0)),
+            (&savings, transaction("s2", 11, 500.0)),
+            (&savings, transaction("s3", 20, -75.0)),
+        ];
+
+        let transfers = match_transfers(
+            transactions.iter().map(|(a, t)| (*a, t)),
+            &TransferOptions::default(),
+        );
+
+        assert_eq!(
+            transfers,
+            vec![Transfer {
+                from: TransactionRef {
+                    account: checking.clone(),
+                    fitid: "c1".to_string(),
+                },
+                to: TransactionRef {
+                    account: savings.clone(),
+                    fitid: "s2".to_string(),
+                },
+                amount: 500.0,
+                days_apart: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn needs_a_transfer_type_or_hint_on_either_side() {
+        let (checking, savings) = (account("1"), account("2"));
+        let salary = Transaction {
+            trntype: "CREDIT".to_string(),
+            name: Some("ACME PAYROLL".to_string()),
+            ..test_support::transaction("s1", (2025, 6, 10), 500.0)
+        };
+        let purchase = Transaction {
+            trntype: "POS".to_string(),
+            name: Some("ELECTRONICS STORE".to_string()),
+            ..test_support::transaction("c1", (2025, 6, 10), -500.0)
+        };
+        let match_pair = |debit: &Transaction, credit: &Transaction| {
+            match_transfers(
+                [(&checking, debit), (&savings, credit)],
+                &TransferOptions::default(),
+            )
+            .len()
+        };
+
+        assert_eq!(match_pair(&purchase, &salary), 0);
+
+        let hinted = Transaction {
+            name: Some("Transferência enviada".to_string()),
+            ..purchase.clone()
+        };
+        assert_eq!(match_pair(&hinted, &salary), 1);
+    }
+
+    #[test]
+    fn pairs_card_payments_with_the_card_credit() {
+        let (checking, card) = (account("1"), account("2"));
+        let payment = Transaction {
+            trntype: "PAYMENT".to_string(),
+            name: Some("PAYMENT - THANK YOU".to_string()),
+            ..test_support::transaction("c1", (2025, 6, 10), -1234.56)
+        };
+        let credit = Transaction {
+            trntype: "CREDIT".to_string(),
+            name: Some("PAGAMENTO RECEBIDO".to_string()),
+            ..test_support::transaction("k1", (2025, 6, 11), 1234.56)
+        };
+
+        let transfers = match_transfers(
+            [(&checking, &payment), (&card, &credit)],
+            &TransferOptions::default(),
+        );
+
+        assert_eq!(
+            transfers,
+            vec![Transfer {
+                from: TransactionRef {
+                    account: checking.clone(),
+                    fitid: "c1".to_string(),
+                },
+                to: TransactionRef {
+                    account: card.clone(),
+                    fitid: "k1".to_string(),
+                },
+                amount: 1234.56,
+                days_apart: 1,
+            }]
+        );
+    }
+}
@@ -0,0 +1,401 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::domain::entities::ofx::{OfxDateTime, StatementResponse};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IssueKind {
+    TransactionOutsideRange {
+        fitid: String,
+        dtposted: OfxDateTime,
+        dtstart: OfxDateTime,
+        dtend: OfxDateTime,
+    },
+    DuplicateFitid {
+        fitid: String,
+        count: usize,
+    },
+    /// `LEDGERBAL` differs from the previous statement's balance plus the transactions since.
+    LedgerBalanceMismatch {
+        expected: f64,
+        actual: f64,
+    },
+    AvailableExceedsLedger {
+        availbal: f64,
+        ledgerbal: f64,
+    },
+    EmptyTransactionType {
+        fitid: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub kind: IssueKind,
+}
+
+impl ValidationIssue {
+    /// The transaction the issue is about, when it concerns a single one.
+    pub fn fitid(&self) -> Option<&str> {
+        match &self.kind {
+            IssueKind::TransactionOutsideRange { fitid, .. }
+            | IssueKind::DuplicateFitid { fitid, .. }
+            | IssueKind::EmptyTransactionType { fitid } => Some(fitid),
+            IssueKind::LedgerBalanceMismatch { .. } | IssueKind::AvailableExceedsLedger { .. } => {
+                None
+            }
+        }
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            IssueKind::TransactionOutsideRange {
+                fitid,
+                dtposted,
+                dtstart,
+                dtend,
+            } => write!(
+                f,
+                "Transaction {fitid} posted {dtposted} is outside the statement period {dtstart} to {dtend}"
+            ),
+            IssueKind::DuplicateFitid { fitid, count } => {
+                write!(f, "FITID {fitid} appears {count} times")
+            }
+            IssueKind::LedgerBalanceMismatch { expected, actual } => write!(
+                f,
+                "Ledger balance {actual:.2} does not reconcile with the previous statement (expected {expected:.2})"
+            ),
+            IssueKind::AvailableExceedsLedger {
+                availbal,
+                ledgerbal,
+            } => write!(
+                f,
+                "Available balance {availbal:.2} is greater than ledger balance {ledgerbal:.2}"
+            ),
+            IssueKind::EmptyTransactionType { fitid } => {
+                write!(f, "Transaction {fitid} has an empty TRNTYPE")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ValidationOptions {
+    /// Largest difference between two amounts that still counts as equal.
+    pub tolerance: f64,
+    /// Account types where an available balance above the ledger balance is expected, such as
+    /// credit lines where the available amount is the unused credit.
+    pub available_above_ledger_accttypes: Vec<String>,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        ValidationOptions {
+            tolerance: 0.005,
+            available_above_ledger_accttypes: vec!["CREDITLINE".to_string()],
+        }
+    }
+}
+
+/// Checks a statement for internal consistency and, when the previous statement of the same
+/// account is given, for continuity of its ledger balance.
+pub fn validate_statement(
+    statement: &StatementResponse,
+    previous: Option<&StatementResponse>,
+    options: &ValidationOptions,
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+
+    if let Some(list) = &statement.banktranlist {
+        let mut fitid_counts: BTreeMap<&str, usize> = BTreeMap::new();
+
+        for transaction in &list.transactions {
+            *fitid_counts.entry(&transaction.fitid).or_default() += 1;
+
+            if transaction.dtposted.datetime < list.dtstart.datetime
+                || transaction.dtposted.datetime > list.dtend.datetime
+            {
+                issues.push(ValidationIssue {
+                    severity: Severity::Warning,
+                    kind: IssueKind::TransactionOutsideRange {
+                        fitid: transaction.fitid.clone(),
+                        dtposted: transaction.dtposted.clone(),
+                        dtstart: list.dtstart.clone(),
+                        dtend: list.dtend.clone(),
+                    },
+                });
+            }
+
+            if transaction.trntype.trim().is_empty() {
+                issues.push(ValidationIssue {
+                    severity: Severity::Error,
+                    kind: IssueKind::EmptyTransactionType {
+                        fitid: transaction.fitid.clone(),
+                    },
+                });
+            }
+        }
+
+        issues.extend(
+            fitid_counts
+                .into_iter()
+                .filter(|(_, count)| *count > 1)
+                .map(|(fitid, count)| ValidationIssue {
+                    severity: Severity::Error,
+                    kind: IssueKind::DuplicateFitid {
+                        fitid: fitid.to_string(),
+                        count,
+                    },
+                }),
+        );
+    }
+
+    if let (Some(available), Some(ledger)) = (&statement.availbal, &statement.ledgerbal) {
+        let expected = options
+            .available_above_ledger_accttypes
+            .iter()
+            .any(|t| t.eq_ignore_ascii_case(&statement.bankacctfrom.accttype));
+
+        if !expected && available.balamt > ledger.balamt + options.tolerance {
+            issues.push(ValidationIssue {
+                severity: Severity::Warning,
+                kind: IssueKind::AvailableExceedsLedger {
+                    availbal: available.balamt,
+                    ledgerbal: ledger.balamt,
+                },
+            });
+        }
+    }
+
+    if let Some(issue) = previous.and_then(|p| reconcile_ledger(p, statement, options)) {
+        issues.push(issue);
+    }
+
+    issues
+}
+
+/// The previous ledger balance plus every effective transaction posted after it, up to the
+/// current `DTASOF`, should give the current ledger balance.
+fn reconcile_ledger(
+    previous: &StatementResponse,
+    current: &StatementResponse,
+    options: &ValidationOptions,
+) -> Option<ValidationIssue> {
+    if previous.bankacctfrom.key() != current.bankacctfrom.key() {
+        return None;
+    }
+
+    let (Some(opening), Some(closing), Some(list)) = (
+        &previous.ledgerbal,
+        &current.ledgerbal,
+        &current.banktranlist,
+    ) else {
+        return None;
+    };
+
+    let movement: f64 = list
+        .effective_transactions()
+        .iter()
+        .filter(|t| {
+            t.dtposted.datetime > opening.dtasof.datetime
+                && t.dtposted.datetime <= closing.dtasof.datetime
+        })
+        .map(|t| t.trnamt)
+        .sum();
+    let expected = opening.balamt + movement;
+
+    ((expected - closing.balamt).abs() > options.tolerance).then_some(ValidationIssue {
+        severity: Severity::Error,
+        kind: IssueKind::LedgerBalanceMismatch {
+            expected,
+            actual: closing.balamt,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::entities::ofx::{CorrectAction, Transaction},
+        test_support::{balance, datetime, statement, transaction},
+    };
+    use pretty_assertions::assert_eq;
+
+    fn statement_response(transactions: Vec<Transaction>) -> StatementResponse {
+        statement("1", transactions).stmtrs
+    }
+
+    fn kinds(issues: Vec<ValidationIssue>) -> Vec<(Severity, IssueKind)> {
+        issues.into_iter().map(|i| (i.severity, i.kind)).collect()
+    }
+
+    #[test]
+    fn flags_transactions_outside_the_period() {
+        let mut current = statement_response(vec![
+            transaction("1", (2025, 6, 1), -10.0),
+            transaction("2", (2025, 6, 20), -20.0),
+        ]);
+        let list = current.banktranlist.as_mut().unwrap();
+        list.dtend = datetime((2025, 6, 15));
+
+        assert_eq!(
+            kinds(validate_statement(
+                &current,
+                None,
+                &ValidationOptions::default()
+            )),
+            vec![(
+                Severity::Warning,
+                IssueKind::TransactionOutsideRange {
+                    fitid: "2".to_string(),
+                    dtposted: datetime((2025, 6, 20)),
+                    dtstart: datetime((2025, 6, 1)),
+                    dtend: datetime((2025, 6, 15)),
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn flags_duplicate_fitids() {
+        let current = statement_response(vec![
+            transaction("1", (2025, 6, 1), -10.0),
+            transaction("1", (2025, 6, 2), -10.0),
+            transaction("2", (2025, 6, 3), -10.0),
+        ]);
+
+        assert_eq!(
+            kinds(validate_statement(
+                &current,
+                None,
+                &ValidationOptions::default()
+            )),
+            vec![(
+                Severity::Error,
+                IssueKind::DuplicateFitid {
+                    fitid: "1".to_string(),
+                    count: 2,
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn flags_empty_transaction_types() {
+        let current = statement_response(vec![Transaction {
+            trntype: " ".to_string(),
+            ..transaction("1", (2025, 6, 1), -10.0)
+        }]);
+
+        assert_eq!(
+            kinds(validate_statement(
+                &current,
+                None,
+                &ValidationOptions::default()
+            )),
+            vec![(
+                Severity::Error,
+                IssueKind::EmptyTransactionType {
+                    fitid: "1".to_string(),
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn flags_available_above_ledger_except_on_credit_lines() {
+        let mut current = statement_response(Vec::new());
+        current.ledgerbal = Some(balance(100.0, (2025, 6, 30)));
+        current.availbal = Some(balance(150.0, (2025, 6, 30)));
+
+        assert_eq!(
+            kinds(validate_statement(
+                &current,
+                None,
+                &ValidationOptions::default()
+            )),
+            vec![(
+                Severity::Warning,
+                IssueKind::AvailableExceedsLedger {
+                    availbal: 150.0,
+                    ledgerbal: 100.0,
+                }
+            )]
+        );
+
+        current.bankacctfrom.accttype = "CREDITLINE".to_string();
+        assert_eq!(
+            validate_statement(&current, None, &ValidationOptions::default()),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn flags_ledger_balance_not_following_the_previous_statement() {
+        let mut previous = statement_response(vec![transaction("1", (2025, 5, 20), -10.0)]);
+        previous.ledgerbal = Some(balance(100.0, (2025, 5, 31)));
+        let mut current = statement_response(vec![
+            transaction("2", (2025, 6, 2), -20.0),
+            transaction("3", (2025, 6, 3), 50.0),
+        ]);
+        current.ledgerbal = Some(balance(120.0, (2025, 6, 30)));
+
+        assert_eq!(
+            kinds(validate_statement(
+                &current,
+                Some(&previous),
+                &ValidationOptions::default()
+            )),
+            vec![(
+                Severity::Error,
+                IssueKind::LedgerBalanceMismatch {
+                    expected: 130.0,
+                    actual: 120.0,
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn reconciles_effective_transactions_between_balance_dates() {
+        let mut previous = statement_response(Vec::new());
+        previous.ledgerbal = Some(balance(100.0, (2025, 5, 31)));
+        let mut current = statement_response(vec![
+            // Already counted in the previous balance
+            transaction("0", (2025, 5, 30), -5.0),
+            transaction("1", (2025, 6, 2), -20.0),
+            Transaction {
+                correctfitid: Some("1".to_string()),
+                correctaction: Some(CorrectAction::Replace),
+                ..transaction("1b", (2025, 6, 3), -25.0)
+            },
+            // Posted after the closing balance
+            transaction("2", (2025, 7, 2), -40.0),
+        ]);
+        current.ledgerbal = Some(balance(75.0, (2025, 6, 30)));
+        let options = ValidationOptions::default();
+
+        assert_eq!(reconcile_ledger(&previous, &current, &options), None);
+
+        current.ledgerbal = Some(balance(80.0, (2025, 6, 30)));
+        assert_eq!(
+            reconcile_ledger(&previous, &current, &options).map(|i| i.kind),
+            Some(IssueKind::LedgerBalanceMismatch {
+                expected: 75.0,
+                actual: 80.0,
+            })
+        );
+
+        previous.bankacctfrom.acctid = "2".to_string();
+        assert_eq!(reconcile_ledger(&previous, &current, &options), None);
+    }
+}
@@ -1,2 +1,5 @@
 pub mod adapters;
 pub mod domain;
+
+#[cfg(test)]
+mod test_support;
@@ -0,0 +1,124 @@
+//! Fixtures shared by the unit tests.
+
+use std::collections::BTreeMap;
+
+use chrono::{FixedOffset, TimeZone};
+
+use crate::domain::entities::ofx::{
+    AccountKey, Balance, BankAccount, BankTransactionList, IntuitExtensions, OfxBody, OfxDateTime,
+    OfxDocument, OfxHeader, SignonResponse, StatementResponse, StatementTransactionResponse,
+    Status, Transaction,
+};
+
+/// `y-m-d` at `hour:min`, three hours behind UTC like the sample statements.
+pub fn datetime_at((y, m, d): (i32, u32, u32), hour: u32, min: u32) -> OfxDateTime {
+    OfxDateTime::from(
+        FixedOffset::west_opt(3 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(y, m, d, hour, min, 0)
+            .unwrap(),
+    )
+}
+
+/// `y-m-d` at noon.
+pub fn datetime(date: (i32, u32, u32)) -> OfxDateTime {
+    datetime_at(date, 12, 0)
+}
+
+/// A `DEBIT` posted at noon, without name, memo or currency.
+pub fn transaction(fitid: &str, date: (i32, u32, u32), trnamt: f64) -> Transaction {
+    Transaction {
+        trntype: "DEBIT".to_string(),
+        dtposted: datetime(date),
+        trnamt,
+        fitid: fitid.to_string(),
+        name: None,
+        memo: None,
+        correctfitid: None,
+        correctaction: None,
+        currency: None,
+        origcurrency: None,
+        unknown: Vec::new(),
+    }
+}
+
+/// A checking account at bank `0001`.
+pub fn account(acctid: &str) -> AccountKey {
+    AccountKey {
+        bankid: "0001".to_string(),
+        acctid: acctid.to_string(),
+        accttype: "CHECKING".to_string(),
+    }
+}
+
+/// A balance as of `y-m-d` at noon.
+pub fn balance(balamt: f64, date: (i32, u32, u32)) -> Balance {
+    Balance {
+        balamt,
+        dtasof: datetime(date),
+        unknown: Vec::new(),
+    }
+}
+
+/// A BRL statement for checking account `acctid` spanning its transactions, without balances.
+pub fn statement(acctid: &str, transactions: Vec<Transaction>) -> StatementTransactionResponse {
+    let dates = transactions.iter().map(|t| t.dtposted.clone());
+    let dtstart = dates.clone().min_by_key(|d| d.datetime);
+    let dtend = dates.max_by_key(|d| d.datetime);
+    StatementTransactionResponse {
+        trnuid: "1".to_string(),
+        status: status(),
+        stmtrs: StatementResponse {
+            curdef: "BRL".to_string(),
+            bankacctfrom: BankAccount {
+                bankid: "0001".to_string(),
+                acctid: acctid.to_string(),
+                accttype: "CHECKING".to_string(),
+                unknown: Vec::new(),
+            },
+            banktranlist: Some(BankTransactionList {
+                dtstart: dtstart.unwrap_or_else(|| datetime((2025, 1, 1))),
+                dtend: dtend.unwrap_or_else(|| datetime((2025, 1, 1))),
+                transactions,
+                unknown: Vec::new(),
+            }),
+            ledgerbal: None,
+            availbal: None,
+            extensions: IntuitExtensions::default(),
+            unknown: Vec::new(),
+        },
+        unknown: Vec::new(),
+    }
+}
+
+/// A document carrying the given bank statements and nothing else.
+pub fn document(statements: Vec<StatementTransactionResponse>) -> OfxDocument {
+    OfxDocument {
+        header: OfxHeader::default(),
+        body: OfxBody {
+            sign_on_response: SignonResponse {
+                status: status(),
+                dtserver: datetime((2025, 1, 1)),
+                language: None,
+                dtprofup: None,
+                fi: None,
+                extensions: IntuitExtensions::default(),
+                unknown: Vec::new(),
+            },
+            bank_msgs: statements,
+            profile_msgs: Vec::new(),
+            unknown: Vec::new(),
+            message_set_unknown: BTreeMap::new(),
+        },
+        source: None,
+    }
+}
+
+fn status() -> Status {
+    Status {
+        code: 0,
+        severity: "INFO".to_string(),
+        message: None,
+        unknown: Vec::new(),
+    }
+}
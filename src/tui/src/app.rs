@@ -0,0 +1,1140 @@
+use std::{cmp::Ordering, path::PathBuf};
+
+use chrono::NaiveDate;
+use ofx_parser::{
+    adapters::{
+        annotation_file::save_annotations,
+        filter_expression::parse_filter,
+        ofx_parser::ParseWarning,
+        report_export::export_report,
+        split_expression::{format_splits, parse_splits},
+    },
+    domain::{
+        entities::{
+            annotation::{Annotation, Annotations},
+            ofx::{AccountKey, Balance, OfxDateTime, Transaction, TransactionRef},
+        },
+        services::{
+            anomalies::AnomalyReason,
+            budget::{Budget, MonthBudget, budget_vs_actual},
+            categorization::Categorization,
+            filter::TransactionFilter,
+            forecast::{Forecast, ForecastDay},
+            recurring::RecurringSeries,
+            reports::{GroupBy, Period, Report, ReportOptions, summarize},
+            validation::ValidationIssue,
+        },
+    },
+};
+use ratatui::widgets::TableState;
+
+use crate::ui::format_amount;
+
+pub struct TransactionRow {
+    pub account: AccountKey,
+    pub transaction: Transaction,
+    /// Cleaned up name, the raw one stays in `transaction.name`.
+    pub payee: String,
+    /// Running balance, when the statement carries a ledger balance to anchor it on.
+    pub balance: Option<f64>,
+    pub category: Option<Categorization>,
+    /// One side of a transfer between two of the loaded accounts.
+    pub transfer: bool,
+    /// Why the transaction looks unusual, if it does.
+    pub anomalies: Vec<AnomalyReason>,
+    /// Where the transaction was first read from, none when it only came from the database.
+    pub statement: Option<Statement>,
+    /// What validating that statement found about the transaction.
+    pub issues: Vec<ValidationIssue>,
+}
+
+/// A statement in one of the files loaded.
+#[derive(Debug, Clone)]
+pub struct Statement {
+    pub path: PathBuf,
+    pub dtstart: OfxDateTime,
+    pub dtend: OfxDateTime,
+}
+
+impl TransactionRow {
+    pub fn reference(&self) -> TransactionRef {
+        TransactionRef {
+            account: self.account.clone(),
+            fitid: self.transaction.fitid.clone(),
+        }
+    }
+}
+
+/// A file given on the command line, or found in a directory given there.
+pub struct SourceFile {
+    pub path: PathBuf,
+    pub status: SourceStatus,
+}
+
+impl SourceFile {
+    pub fn failed(&self) -> bool {
+        matches!(self.status, SourceStatus::Failed { .. })
+    }
+
+    /// Whether the file failed to parse or parsed with warnings.
+    pub fn has_problems(&self) -> bool {
+        match &self.status {
+            SourceStatus::Loaded { warnings, .. } => !warnings.is_empty(),
+            SourceStatus::Failed { .. } => true,
+        }
+    }
+}
+
+pub enum SourceStatus {
+    /// `duplicates` of the file's transactions were already loaded from another file.
+    Loaded {
+        transactions: usize,
+        duplicates: usize,
+        /// What lenient parsing worked around.
+        warnings: Vec<ParseWarning>,
+    },
+    Failed {
+        message: String,
+        /// Line and column the parser stopped at, when it knows.
+        location: Option<(usize, usize)>,
+        /// The file's text on that line.
+        excerpt: Option<String>,
+    },
+}
+
+impl SourceStatus {
+    /// The line a failed parse stopped at, numbered, and a caret under the column it stopped
+    /// at. Long lines are cut to the 30 characters before that column and what follows.
+    pub fn excerpt(&self) -> Option<[String; 2]> {
+        let SourceStatus::Failed {
+            location: Some((line, column)),
+            excerpt: Some(excerpt),
+            ..
+        } = self
+        else {
+            return None;
+        };
+        let skipped = column.saturating_sub(31);
+        let (text, column) = match skipped {
+            0 => (excerpt.clone(), *column),
+            _ => (
+                format!("…{}", excerpt.chars().skip(skipped + 1).collect::<String>()),
+                column - skipped,
+            ),
+        };
+        Some([
+            format!("{line:>5} | {text}"),
+            format!("      | {:>column$}", "^"),
+        ])
+    }
+}
+
+/// A statement account, shown on a tab of its own.
+pub struct Account {
+    pub key: AccountKey,
+    pub curdef: String,
+    /// Most recent balances across the statements loaded.
+    pub ledgerbal: Option<Balance>,
+    pub availbal: Option<Balance>,
+    /// Earliest start and latest end of the statements loaded.
+    pub period: Option<(OfxDateTime, OfxDateTime)>,
+    /// Balance forecast, for accounts with a ledger balance.
+    pub forecast: Option<Forecast>,
+}
+
+/// Totals of the rows on screen, shown over the transaction table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub transactions: usize,
+    /// Whether a filter narrows the rows.
+    pub filtered: bool,
+    /// First and last posting dates of the rows.
+    pub posted: Option<(NaiveDate, NaiveDate)>,
+    /// Earliest start and latest end of the statements of the accounts on the tab, whatever
+    /// the filter.
+    pub statement_period: Option<(NaiveDate, NaiveDate)>,
+    pub inflow: f64,
+    pub outflow: f64,
+    pub net: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum View {
+    Transactions,
+    Subscriptions,
+    Reports,
+    Budgets,
+    Forecast,
+    /// Files that failed to parse or parsed with warnings.
+    Errors,
+}
+
+/// Transaction table columns rows can be sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortColumn {
+    Date,
+    Type,
+    Amount,
+    Name,
+    Memo,
+}
+
+impl SortColumn {
+    /// Header of the column sorted by.
+    pub fn header(self) -> &'static str {
+        match self {
+            SortColumn::Date => "Date",
+            SortColumn::Type => "Type",
+            SortColumn::Amount => "Amount",
+            SortColumn::Name => "Payee",
+            SortColumn::Memo => "Memo",
+        }
+    }
+
+    fn compare(self, a: &TransactionRow, b: &TransactionRow) -> Ordering {
+        let (a_t, b_t) = (&a.transaction, &b.transaction);
+        match self {
+            SortColumn::Date => a_t.dtposted.datetime.cmp(&b_t.dtposted.datetime),
+            SortColumn::Type => a_t.trntype.cmp(&b_t.trntype),
+            SortColumn::Amount => a_t.trnamt.total_cmp(&b_t.trnamt),
+            SortColumn::Name => a.payee.to_lowercase().cmp(&b.payee.to_lowercase()),
+            SortColumn::Memo => a_t
+                .memo
+                .as_ref()
+                .map(|m| m.to_lowercase())
+                .cmp(&b_t.memo.as_ref().map(|m| m.to_lowercase())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sort {
+    pub column: SortColumn,
+    pub descending: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnnotationField {
+    Notes,
+    Tags,
+    Splits,
+}
+
+impl AnnotationField {
+    pub fn prompt(self) -> &'static str {
+        match self {
+            AnnotationField::Notes => "Notes",
+            AnnotationField::Tags => "Tags (comma separated)",
+            AnnotationField::Splits => "Splits (Category=amount \"memo\"; Category takes the rest)",
+        }
+    }
+}
+
+/// What a line being edited in the footer is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputField {
+    Annotation(AnnotationField),
+    /// Searched for as it is typed.
+    Search,
+    Filter,
+}
+
+impl InputField {
+    pub fn prompt(self) -> &'static str {
+        match self {
+            InputField::Annotation(field) => field.prompt(),
+            InputField::Search => "/",
+            InputField::Filter => "Filter (e.g. amount<-100 type:POS name~uber)",
+        }
+    }
+}
+
+/// A line being edited in the footer.
+pub struct Input {
+    pub field: InputField,
+    pub buffer: String,
+}
+
+pub struct App {
+    pub txs: Vec<TransactionRow>,
+    pub accounts: Vec<Account>,
+    /// Files the transactions were loaded from, none when only reading a database.
+    pub sources: Vec<SourceFile>,
+    /// Whether the sources were parsed leniently.
+    pub lenient: bool,
+    /// Selected tab: 0 for all accounts, otherwise one past the index into `accounts`.
+    pub account_tab: usize,
+    /// Indexes into `txs` of the rows on screen, in display order.
+    pub visible: Vec<usize>,
+    /// Text searched for in the rows on screen, kept after the search line is closed so n/N
+    /// can move between matches.
+    pub search: Option<String>,
+    /// The filter expression narrowing the rows on screen, as typed.
+    pub filter_expression: Option<String>,
+    pub filter: TransactionFilter,
+    /// Order of the rows on screen, the order they were loaded in when unset.
+    pub sort: Option<Sort>,
+    /// Selection and scroll position within `visible`.
+    pub state: TableState,
+    pub scroll: usize,
+    pub view: View,
+    pub subscriptions: Vec<RecurringSeries>,
+    pub subscriptions_state: TableState,
+    pub report_options: ReportOptions,
+    pub report: Report,
+    pub reports_state: TableState,
+    /// One-off message shown in the footer, such as the outcome of an export.
+    pub status: Option<String>,
+    pub annotations: Annotations,
+    /// Sidecar file edits are saved to.
+    pub annotations_path: PathBuf,
+    pub input: Option<Input>,
+    pub budget: Budget,
+    /// Budget against actual spending for every month loaded, oldest first.
+    pub budgets: Vec<MonthBudget>,
+    /// Index into `budgets` of the month on screen.
+    pub budget_month: usize,
+    pub budgets_state: TableState,
+    /// Which of the accounts with a forecast is on screen.
+    pub forecast_account: usize,
+    pub forecast_state: TableState,
+    pub errors_state: TableState,
+    /// Whether every field of the selected transaction is shown over the table.
+    pub detail: bool,
+}
+
+impl App {
+    pub fn new(
+        txs: Vec<TransactionRow>,
+        accounts: Vec<Account>,
+        subscriptions: Vec<RecurringSeries>,
+        report_options: ReportOptions,
+        annotations: Annotations,
+        annotations_path: PathBuf,
+        budget: Budget,
+    ) -> Self {
+        let mut s = TableState::default();
+        if !txs.is_empty() {
+            s.select(Some(0));
+        }
+        let mut subscriptions_state = TableState::default();
+        if !subscriptions.is_empty() {
+            subscriptions_state.select(Some(0));
+        }
+        let report = summarize(
+            txs.iter().map(|r| (&r.account, &r.transaction)),
+            &report_options,
+        );
+        let visible = (0..txs.len()).collect();
+        let mut app = Self {
+            txs,
+            accounts,
+            sources: Vec::new(),
+            lenient: false,
+            account_tab: 0,
+            visible,
+            search: None,
+            filter_expression: None,
+            filter: TransactionFilter::default(),
+            sort: None,
+            state: s,
+            scroll: 0,
+            view: View::Transactions,
+            subscriptions,
+            subscriptions_state,
+            report_options,
+            report,
+            reports_state: TableState::default(),
+            status: None,
+            annotations,
+            annotations_path,
+            input: None,
+            budget,
+            budgets: Vec::new(),
+            budget_month: 0,
+            budgets_state: TableState::default(),
+            forecast_account: 0,
+            forecast_state: TableState::default(),
+            errors_state: TableState::default(),
+            detail: false,
+        };
+        app.select_latest_period();
+        app.refresh_budgets();
+        if !app.forecast_days().is_empty() {
+            app.forecast_state.select(Some(0));
+        }
+        app
+    }
+
+    /// Moves the budget screen `delta` months, staying within the loaded ones.
+    pub fn shift_budget_month(&mut self, delta: isize) {
+        let max = self.budgets.len().saturating_sub(1);
+        self.budget_month = self.budget_month.saturating_add_signed(delta).min(max);
+    }
+
+    pub fn current_budget(&self) -> Option<&MonthBudget> {
+        self.budgets.get(self.budget_month)
+    }
+
+    /// Opens on the errors view when any of the files failed to parse.
+    pub fn with_sources(mut self, sources: Vec<SourceFile>, lenient: bool) -> Self {
+        if sources.iter().any(SourceFile::failed) {
+            self.view = View::Errors;
+        }
+        self.sources = sources;
+        self.lenient = lenient;
+        if !self.problems().is_empty() {
+            self.errors_state.select(Some(0));
+        }
+        self
+    }
+
+    /// Files that failed to parse or parsed with warnings, in the order they were loaded.
+    pub fn problems(&self) -> Vec<&SourceFile> {
+        self.sources.iter().filter(|s| s.has_problems()).collect()
+    }
+
+    /// Moves to another account tab, wrapping around, and keeps only its rows on screen.
+    pub fn cycle_account_tab(&mut self, delta: isize) {
+        let tabs = self.accounts.len() as isize + 1;
+        self.account_tab = (self.account_tab as isize + delta).rem_euclid(tabs) as usize;
+        self.refresh_visible();
+        self.state.select((!self.visible.is_empty()).then_some(0));
+        self.scroll = 0;
+    }
+
+    /// Narrows the rows to those of the selected tab that pass the filter, keeping the
+    /// selection on the same transaction when it is still there.
+    fn refresh_visible(&mut self) {
+        let selected = self.visible.get(self.selected()).copied();
+        let account = self.current_account().map(|a| a.key.clone());
+        self.visible = (0..self.txs.len())
+            .filter(|&i| {
+                account
+                    .as_ref()
+                    .is_none_or(|key| &self.txs[i].account == key)
+            })
+            .filter(|&i| self.filter.matches(&self.txs[i].transaction))
+            .collect();
+        if let Some(sort) = self.sort {
+            // Stable, so rows that compare equal stay in load order
+            self.visible.sort_by(|&a, &b| {
+                let ordering = sort.column.compare(&self.txs[a], &self.txs[b]);
+                if sort.descending {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            });
+        }
+
+        let index = selected
+            .and_then(|s| self.visible.iter().position(|&i| i == s))
+            .unwrap_or(0);
+        self.state
+            .select((!self.visible.is_empty()).then_some(index));
+    }
+
+    /// Sorts by the next column, going back to load order after the last one.
+    pub fn cycle_sort_column(&mut self) {
+        let column = match self.sort.map(|s| s.column) {
+            None => Some(SortColumn::Date),
+            Some(SortColumn::Date) => Some(SortColumn::Type),
+            Some(SortColumn::Type) => Some(SortColumn::Amount),
+            Some(SortColumn::Amount) => Some(SortColumn::Name),
+            Some(SortColumn::Name) => Some(SortColumn::Memo),
+            Some(SortColumn::Memo) => None,
+        };
+        self.sort = column.map(|column| Sort {
+            column,
+            descending: self.sort.is_some_and(|s| s.descending),
+        });
+        self.refresh_visible();
+    }
+
+    /// Flips between ascending and descending, sorting by date if nothing was sorted yet.
+    pub fn toggle_sort_direction(&mut self) {
+        self.sort = Some(match self.sort {
+            Some(sort) => Sort {
+                descending: !sort.descending,
+                ..sort
+            },
+            None => Sort {
+                column: SortColumn::Date,
+                descending: true,
+            },
+        });
+        self.refresh_visible();
+    }
+
+    /// Opens the footer on the filter, filled with the one applied.
+    pub fn start_filter(&mut self) {
+        self.input = Some(Input {
+            field: InputField::Filter,
+            buffer: self.filter_expression.clone().unwrap_or_default(),
+        });
+    }
+
+    /// Applies a filter expression, an empty one showing every row again. An invalid one is
+    /// left open in the footer to be fixed.
+    fn apply_filter(&mut self, expression: String) {
+        match parse_filter(&expression) {
+            Ok(filter) => {
+                self.filter = filter;
+                self.filter_expression = (!expression.trim().is_empty()).then_some(expression);
+                self.refresh_visible();
+            }
+            Err(e) => {
+                self.status = Some(e.to_string());
+                self.input = Some(Input {
+                    field: InputField::Filter,
+                    buffer: expression,
+                });
+            }
+        }
+    }
+
+    pub fn start_search(&mut self) {
+        self.search = None;
+        self.input = Some(Input {
+            field: InputField::Search,
+            buffer: String::new(),
+        });
+    }
+
+    /// Searches for what has been typed so far, moving to the first match from the selection on.
+    pub fn update_search(&mut self, viewport: usize) {
+        let Some(input) = self
+            .input
+            .as_ref()
+            .filter(|i| i.field == InputField::Search)
+        else {
+            return;
+        };
+        self.search = (!input.buffer.is_empty()).then(|| input.buffer.clone());
+        if let Some(index) = self.find_match(self.selected(), 1) {
+            self.select(index, viewport);
+        }
+    }
+
+    /// Moves to the next match of the search, or the previous one when `delta` is negative,
+    /// wrapping around.
+    pub fn next_match(&mut self, delta: isize, viewport: usize) {
+        let len = self.visible.len() as isize;
+        if len == 0 {
+            return;
+        }
+        let from = (self.selected() as isize + delta).rem_euclid(len) as usize;
+        if let Some(index) = self.find_match(from, delta) {
+            self.select(index, viewport);
+        }
+    }
+
+    /// First position in `visible` from `from` on, going in the direction of `delta`, whose
+    /// row matches the search.
+    fn find_match(&self, from: usize, delta: isize) -> Option<usize> {
+        let len = self.visible.len() as isize;
+        (0..len)
+            .map(|step| (from as isize + step * delta.signum()).rem_euclid(len) as usize)
+            .find(|&index| self.matches_search(&self.txs[self.visible[index]]))
+    }
+
+    /// Whether the name, memo, type or amount of the row contains the search, ignoring case.
+    pub fn matches_search(&self, row: &TransactionRow) -> bool {
+        let Some(search) = &self.search else {
+            return false;
+        };
+        let search = search.to_lowercase();
+        let t = &row.transaction;
+        [
+            t.name.as_deref(),
+            Some(row.payee.as_str()),
+            t.memo.as_deref(),
+            Some(t.trntype.as_str()),
+            Some(&format!("{:.2}", t.trnamt)),
+            Some(&format_amount(t.trnamt)),
+        ]
+        .into_iter()
+        .flatten()
+        .any(|text| text.to_lowercase().contains(&search))
+    }
+
+    /// The account of the selected tab, `None` on the all accounts one.
+    pub fn current_account(&self) -> Option<&Account> {
+        self.account_tab
+            .checked_sub(1)
+            .and_then(|i| self.accounts.get(i))
+    }
+
+    /// The account on the selected tab, or every account on the all accounts one.
+    pub fn tab_accounts(&self) -> Vec<&Account> {
+        match self.current_account() {
+            Some(account) => vec![account],
+            None => self.accounts.iter().collect(),
+        }
+    }
+
+    /// Totals of the rows on screen.
+    pub fn summary(&self) -> Summary {
+        let rows = self.visible.iter().map(|&i| &self.txs[i].transaction);
+        let amounts = rows.clone().map(|t| t.trnamt);
+        let inflow: f64 = amounts.clone().filter(|a| *a > 0.0).sum();
+        let outflow: f64 = amounts.filter(|a| *a < 0.0).sum();
+        let dates = rows.map(|t| t.dtposted.datetime.date_naive());
+        let periods = self
+            .tab_accounts()
+            .into_iter()
+            .filter_map(|a| a.period.as_ref());
+        let starts = periods
+            .clone()
+            .map(|(start, _)| start.datetime.date_naive());
+        let ends = periods.map(|(_, end)| end.datetime.date_naive());
+        Summary {
+            transactions: self.visible.len(),
+            filtered: self.filter_expression.is_some(),
+            posted: dates.clone().min().zip(dates.max()),
+            statement_period: starts.min().zip(ends.max()),
+            inflow,
+            outflow,
+            net: inflow + outflow,
+        }
+    }
+
+    /// The selected transaction.
+    pub fn selected_row(&self) -> Option<&TransactionRow> {
+        self.visible.get(self.selected()).map(|&i| &self.txs[i])
+    }
+
+    /// Accounts that have a forecast, with it.
+    pub fn forecasts(&self) -> impl Iterator<Item = (&AccountKey, &Forecast)> {
+        self.accounts
+            .iter()
+            .filter_map(|a| Some((&a.key, a.forecast.as_ref()?)))
+    }
+
+    /// Moves the forecast screen to another account, wrapping around.
+    pub fn cycle_forecast_account(&mut self, delta: isize) {
+        let len = self.forecasts().count() as isize;
+        if len == 0 {
+            return;
+        }
+        self.forecast_account = (self.forecast_account as isize + delta).rem_euclid(len) as usize;
+        self.forecast_state.select(Some(0));
+    }
+
+    pub fn current_forecast(&self) -> Option<(&AccountKey, &Forecast)> {
+        self.forecasts().nth(self.forecast_account)
+    }
+
+    /// Days of the current forecast with projected transactions, the ones listed on screen.
+    pub fn forecast_days(&self) -> Vec<&ForecastDay> {
+        self.current_forecast().map_or(Vec::new(), |(_, f)| {
+            f.days
+                .iter()
+                .filter(|d| !d.transactions.is_empty())
+                .collect()
+        })
+    }
+
+    fn refresh_budgets(&mut self) {
+        self.budgets = budget_vs_actual(
+            &self.budget,
+            self.txs.iter().map(|r| (&r.account, &r.transaction)),
+            &self.report_options,
+        );
+        self.budget_month = self.budgets.len().saturating_sub(1);
+        if !self.budget.categories.is_empty() {
+            self.budgets_state.select(Some(0));
+        }
+    }
+
+    /// Switches the report to the next period length, keeping the grouping.
+    pub fn cycle_report_period(&mut self) {
+        self.report_options.period = match self.report_options.period {
+            Period::Month => Period::Quarter,
+            Period::Quarter => Period::Year,
+            Period::Year => Period::Month,
+        };
+        self.refresh_report();
+    }
+
+    pub fn cycle_report_grouping(&mut self) {
+        self.report_options.group_by = match self.report_options.group_by {
+            GroupBy::TransactionType => GroupBy::Payee,
+            GroupBy::Payee => GroupBy::Category,
+            GroupBy::Category => GroupBy::TransactionType,
+        };
+        self.refresh_report();
+    }
+
+    /// Writes the current report next to where the viewer was started, as CSV and JSON.
+    pub fn export_report(&mut self) {
+        let stem = format!(
+            "report-{}-{}",
+            self.report_options.period, self.report_options.group_by
+        );
+        let paths = [
+            PathBuf::from(format!("{stem}.csv")),
+            PathBuf::from(format!("{stem}.json")),
+        ];
+        let result = paths
+            .iter()
+            .try_for_each(|p| export_report(&self.report, p));
+
+        self.status = Some(match result {
+            Ok(()) => format!("exported {stem}.csv and {stem}.json"),
+            Err(e) => format!("export failed: {e}"),
+        });
+    }
+
+    pub fn annotation(&self, row: &TransactionRow) -> Option<&Annotation> {
+        self.annotations.get(&row.reference())
+    }
+
+    /// Opens the footer editor on the selected transaction, filled with the current value.
+    pub fn start_editing(&mut self, field: AnnotationField) {
+        let Some(row) = self.selected_row() else {
+            return;
+        };
+        let annotation = self.annotation(row).cloned().unwrap_or_default();
+        let buffer = match field {
+            AnnotationField::Notes => annotation.notes.unwrap_or_default(),
+            AnnotationField::Tags => annotation.tags.join(", "),
+            AnnotationField::Splits => format_splits(&annotation.splits),
+        };
+        self.input = Some(Input {
+            field: InputField::Annotation(field),
+            buffer,
+        });
+    }
+
+    /// Applies the edited line: annotations go on the selected transaction and are saved to the
+    /// sidecar file, a filter narrows the rows and a search is kept for n/N.
+    pub fn finish_editing(&mut self) {
+        let Some(input) = self.input.take() else {
+            return;
+        };
+        let field = match input.field {
+            InputField::Annotation(field) => field,
+            InputField::Search => return,
+            InputField::Filter => return self.apply_filter(input.buffer),
+        };
+        let Some(row) = self.selected_row() else {
+            return;
+        };
+        let trnamt = row.transaction.trnamt;
+        let reference = row.reference();
+        let mut annotation = self.annotation(row).cloned().unwrap_or_default();
+        let value = input.buffer.trim();
+
+        match field {
+            AnnotationField::Notes => {
+                annotation.notes = (!value.is_empty()).then(|| value.to_string());
+            }
+            AnnotationField::Tags => {
+                annotation.tags = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(str::to_string)
+                    .collect();
+            }
+            AnnotationField::Splits => match parse_splits(value, trnamt) {
+                Ok(splits) => annotation.splits = splits,
+                Err(e) => {
+                    self.status = Some(e.to_string());
+                    return;
+                }
+            },
+        }
+
+        if let Err(e) = self.annotations.annotate(reference, annotation, trnamt) {
+            self.status = Some(e.to_string());
+            return;
+        }
+        self.status = Some(
+            match save_annotations(&self.annotations_path, &self.annotations) {
+                Ok(()) => format!("saved to {}", self.annotations_path.display()),
+                Err(e) => format!("saving annotations failed: {e}"),
+            },
+        );
+        self.report_options.annotations = self.annotations.clone();
+        self.refresh_report();
+        self.refresh_budgets();
+    }
+
+    fn refresh_report(&mut self) {
+        self.report = summarize(
+            self.txs.iter().map(|r| (&r.account, &r.transaction)),
+            &self.report_options,
+        );
+        self.select_latest_period();
+    }
+
+    fn select_latest_period(&mut self) {
+        self.reports_state
+            .select(self.report.periods.len().checked_sub(1));
+    }
+
+    pub fn selected(&self) -> usize {
+        self.state.selected().unwrap_or(0)
+    }
+
+    pub fn select(&mut self, index: usize, viewport: usize) {
+        let max = self.visible.len().saturating_sub(1);
+        let idx = index.min(max);
+        self.state.select(Some(idx));
+        // maintain scroll so selected row stays in view
+        if idx < self.scroll {
+            self.scroll = idx;
+        } else if idx >= self.scroll + viewport {
+            self.scroll = idx + 1 - viewport;
+        }
+    }
+
+    /// Number of rows in the current view.
+    pub fn len(&self) -> usize {
+        match self.view {
+            View::Transactions => self.visible.len(),
+            View::Subscriptions => self.subscriptions.len(),
+            View::Reports => self.report.periods.len(),
+            View::Budgets => self.budget.categories.len(),
+            View::Forecast => self.forecast_days().len(),
+            View::Errors => self.problems().len(),
+        }
+    }
+
+    /// Moves the selection of the current view to `index`, clamped to its rows.
+    pub fn select_in_view(&mut self, index: usize, viewport: usize) {
+        match self.view {
+            View::Transactions => self.select(index, viewport),
+            View::Subscriptions => {
+                let max = self.subscriptions.len().saturating_sub(1);
+                self.subscriptions_state.select(Some(index.min(max)));
+            }
+            View::Reports => {
+                let max = self.report.periods.len().saturating_sub(1);
+                self.reports_state.select(Some(index.min(max)));
+            }
+            View::Budgets => {
+                let max = self.budget.categories.len().saturating_sub(1);
+                self.budgets_state.select(Some(index.min(max)));
+            }
+            View::Forecast => {
+                let max = self.forecast_days().len().saturating_sub(1);
+                self.forecast_state.select(Some(index.min(max)));
+            }
+            View::Errors => {
+                let max = self.problems().len().saturating_sub(1);
+                self.errors_state.select(Some(index.min(max)));
+            }
+        }
+    }
+
+    pub fn selected_in_view(&self) -> usize {
+        match self.view {
+            View::Transactions => self.selected(),
+            View::Subscriptions => self.subscriptions_state.selected().unwrap_or(0),
+            View::Reports => self.reports_state.selected().unwrap_or(0),
+            View::Budgets => self.budgets_state.selected().unwrap_or(0),
+            View::Forecast => self.forecast_state.selected().unwrap_or(0),
+            View::Errors => self.errors_state.selected().unwrap_or(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{FixedOffset, TimeZone};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use ofx_parser::domain::entities::annotation::Split;
+
+    fn account(acctid: &str) -> AccountKey {
+        AccountKey {
+            bankid: "0001".to_string(),
+            acctid: acctid.to_string(),
+            accttype: "CHECKING".to_string(),
+        }
+    }
+
+    /// `2025-06-day` at noon, three hours behind UTC.
+    fn datetime(day: u32) -> OfxDateTime {
+        OfxDateTime::from(
+            FixedOffset::west_opt(3 * 3600)
+                .unwrap()
+                .with_ymd_and_hms(2025, 6, day, 12, 0, 0)
+                .unwrap(),
+        )
+    }
+
+    /// A `DEBIT` posted at noon on `2025-06-day` to account `acctid`, paid to `payee`.
+    fn row(acctid: &str, fitid: &str, day: u32, trnamt: f64, payee: &str) -> TransactionRow {
+        TransactionRow {
+            account: account(acctid),
+            transaction: Transaction {
+                trntype: "DEBIT".to_string(),
+                dtposted: datetime(day),
+                trnamt,
+                fitid: fitid.to_string(),
+                name: Some(payee.to_string()),
+                memo: None,
+                correctfitid: None,
+                correctaction: None,
+                currency: None,
+                origcurrency: None,
+                unknown: Vec::new(),
+            },
+            payee: payee.to_string(),
+            balance: None,
+            category: None,
+            transfer: false,
+            anomalies: Vec::new(),
+            statement: None,
+            issues: Vec::new(),
+        }
+    }
+
+    /// An app over `rows`, with a tab for each of their accounts in the order first seen.
+    fn app(rows: Vec<TransactionRow>) -> App {
+        let mut accounts: Vec<Account> = Vec::new();
+        for row in &rows {
+            if accounts.iter().all(|a| a.key != row.account) {
+                accounts.push(Account {
+                    key: row.account.clone(),
+                    curdef: "BRL".to_string(),
+                    ledgerbal: None,
+                    availbal: None,
+                    period: None,
+                    forecast: None,
+                });
+            }
+        }
+        App::new(
+            rows,
+            accounts,
+            Vec::new(),
+            ReportOptions::default(),
+            Annotations::default(),
+            PathBuf::new(),
+            Budget::default(),
+        )
+    }
+
+    fn visible_fitids(app: &App) -> Vec<&str> {
+        app.visible
+            .iter()
+            .map(|&i| app.txs[i].transaction.fitid.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn account_tabs_show_only_their_rows_and_wrap_around() {
+        let mut app = app(vec![
+            row("1", "a1", 1, -10.0, "Bakery"),
+            row("2", "b1", 2, -20.0, "Pharmacy"),
+            row("1", "a2", 3, -30.0, "Market"),
+        ]);
+        assert_eq!(visible_fitids(&app), vec!["a1", "b1", "a2"]);
+
+        app.cycle_account_tab(1);
+        assert_eq!(
+            app.current_account().map(|a| a.key.acctid.as_str()),
+            Some("1")
+        );
+        assert_eq!(visible_fitids(&app), vec!["a1", "a2"]);
+
+        app.cycle_account_tab(1);
+        assert_eq!(visible_fitids(&app), vec!["b1"]);
+
+        app.cycle_account_tab(1);
+        assert_eq!(app.account_tab, 0);
+        assert_eq!(visible_fitids(&app), vec!["a1", "b1", "a2"]);
+
+        app.cycle_account_tab(-1);
+        assert_eq!(visible_fitids(&app), vec!["b1"]);
+    }
+
+    #[test]
+    fn account_tabs_keep_the_filter_applied() {
+        let mut app = app(vec![
+            row("1", "a1", 1, -10.0, "Bakery"),
+            row("2", "b1", 2, -200.0, "Pharmacy"),
+            row("1", "a2", 3, -300.0, "Market"),
+        ]);
+        app.apply_filter("amount<-100".to_string());
+        assert_eq!(visible_fitids(&app), vec!["b1", "a2"]);
+
+        app.cycle_account_tab(1);
+        assert_eq!(visible_fitids(&app), vec!["a2"]);
+        assert_eq!(
+            app.selected_row().map(|r| r.transaction.fitid.as_str()),
+            Some("a2")
+        );
+
+        app.apply_filter(String::new());
+        assert_eq!(visible_fitids(&app), vec!["a1", "a2"]);
+        assert_eq!(
+            app.selected_row().map(|r| r.transaction.fitid.as_str()),
+            Some("a2")
+        );
+    }
+
+    #[test]
+    fn sorting_keeps_the_selected_transaction() {
+        let mut app = app(vec![
+            row("1", "a1", 3, -10.0, "Market"),
+            row("1", "a2", 1, -50.0, "Bakery"),
+            row("1", "a3", 2, -30.0, "Pharmacy"),
+        ]);
+        app.select(1, 10);
+        let selected = |app: &App| app.selected_row().map(|r| r.transaction.fitid.clone());
+        assert_eq!(selected(&app), Some("a2".to_string()));
+
+        app.cycle_sort_column();
+        assert_eq!(app.sort.map(|s| s.column), Some(SortColumn::Date));
+        assert_eq!(visible_fitids(&app), vec!["a2", "a3", "a1"]);
+        assert_eq!(selected(&app), Some("a2".to_string()));
+
+        app.toggle_sort_direction();
+        assert_eq!(visible_fitids(&app), vec!["a1", "a3", "a2"]);
+        assert_eq!(selected(&app), Some("a2".to_string()));
+
+        app.cycle_sort_column();
+        app.cycle_sort_column();
+        assert_eq!(app.sort.map(|s| s.column), Some(SortColumn::Amount));
+        assert_eq!(visible_fitids(&app), vec!["a1", "a3", "a2"]);
+        assert_eq!(selected(&app), Some("a2".to_string()));
+
+        app.cycle_sort_column();
+        assert_eq!(visible_fitids(&app), vec!["a3", "a1", "a2"]);
+        assert_eq!(selected(&app), Some("a2".to_string()));
+    }
+
+    #[test]
+    fn excerpt_puts_the_caret_under_the_column() {
+        // The column points at the `X` in each line
+        for (text, column) in [
+            ("<NAME>São Paulo</X>", 18),
+            ("X", 1),
+            (&*format!("{}X rest", "é".repeat(30)), 31),
+            (&*format!("{}X rest", "é".repeat(31)), 32),
+            (&*format!("{}X rest", "a".repeat(60)), 61),
+        ] {
+            let status = SourceStatus::Failed {
+                message: String::new(),
+                location: Some((7, column)),
+                excerpt: Some(text.to_string()),
+            };
+
+            let [line, caret] = status.excerpt().unwrap();
+
+            let x = line.chars().position(|c| c == 'X');
+            assert_eq!(caret.chars().position(|c| c == '^'), x, "{text}");
+            assert!(line.starts_with("    7 | "));
+            assert!(line.chars().count() <= "    7 | ".len() + 31 + " rest".len());
+        }
+    }
+
+    #[test]
+    fn editing_splits_keeps_their_memos() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut app = app(vec![row("1", "a1", 1, -100.0, "Market")]);
+        app.annotations_path = dir.path().join("annotations.json");
+        let reference = app.txs[0].reference();
+        let groceries = Split {
+            category: "Groceries".to_string(),
+            amount: -60.0,
+            memo: Some("weekly shop".to_string()),
+        };
+        app.annotations
+            .annotate(
+                reference.clone(),
+                Annotation {
+                    splits: vec![
+                        groceries.clone(),
+                        Split {
+                            category: "Household".to_string(),
+                            amount: -40.0,
+                            memo: None,
+                        },
+                    ],
+                    ..Default::default()
+                },
+                -100.0,
+            )
+            .unwrap();
+
+        app.start_editing(AnnotationField::Splits);
+        let input = app.input.as_mut().unwrap();
+        assert_eq!(
+            input.buffer,
+            r#"Groceries=-60.00 "weekly shop"; Household=-40.00"#
+        );
+        input.buffer = r#"Groceries=-60.00 "weekly shop"; Household=-30; Pharmacy"#.to_string();
+        app.finish_editing();
+
+        assert_eq!(
+            app.annotations.get(&reference).map(|a| a.splits.clone()),
+            Some(vec![
+                groceries,
+                Split {
+                    category: "Household".to_string(),
+                    amount: -30.0,
+                    memo: None,
+                },
+                Split {
+                    category: "Pharmacy".to_string(),
+                    amount: -10.0,
+                    memo: None,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn summarizes_the_rows_on_screen() {
+        let mut app = app(vec![
+            row("1", "a1", 2, 500.0, "Salary"),
+            row("1", "a2", 5, -120.0, "Market"),
+            row("2", "b1", 9, -40.0, "Bakery"),
+            row("1", "a3", 12, -30.0, "Pharmacy"),
+        ]);
+        app.accounts[0].period = Some((datetime(1), datetime(15)));
+        app.accounts[1].period = Some((datetime(3), datetime(30)));
+        let date = |day| NaiveDate::from_ymd_opt(2025, 6, day).unwrap();
+
+        assert_eq!(
+            app.summary(),
+            Summary {
+                transactions: 4,
+                filtered: false,
+                posted: Some((date(2), date(12))),
+                statement_period: Some((date(1), date(30))),
+                inflow: 500.0,
+                outflow: -190.0,
+                net: 310.0,
+            }
+        );
+
+        app.cycle_account_tab(1);
+        app.apply_filter("amount<0".to_string());
+        assert_eq!(
+            app.summary(),
+            Summary {
+                transactions: 2,
+                filtered: true,
+                posted: Some((date(5), date(12))),
+                statement_period: Some((date(1), date(15))),
+                inflow: 0.0,
+                outflow: -150.0,
+                net: -150.0,
+            }
+        );
+
+        app.apply_filter("name~nothing".to_string());
+        assert_eq!(app.summary().posted, None);
+        assert_eq!(app.summary().net, 0.0);
+    }
+}
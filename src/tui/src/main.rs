@@ -1,80 +1,126 @@
 // crates/ofx-tui/src/main.rs
+mod app;
+mod ui;
+
+use chrono_tz::Tz;
 use clap::Parser;
 use crossterm::{
     event::{self, Event, KeyCode, KeyEventKind},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
-use ofx_parser::{adapters::ofx_parser::OfxParser, domain::entities::ofx::Transaction};
-use ratatui::{
-    Terminal,
-    backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
-    text::Line,
-    widgets::{Block, Borders, Cell, Row, Table, TableState},
+use ofx_parser::{
+    adapters::{
+        annotation_file::{load_annotations, sidecar_path},
+        budget_file::load_budget,
+        ofx_parser::{OfxParser, ParseOptions, ParseWarning},
+        rules_file::{load_payee_aliases, load_rules},
+        sqlite_store::{BalanceKind, SqliteStore},
+        timezone::resolve_zone,
+    },
+    domain::{
+        entities::ofx::{
+            AccountKey, Balance, OfxDateTime, OfxDocument, Transaction, TransactionRef,
+        },
+        services::{
+            anomalies::{AnomalyOptions, detect_anomalies},
+            budget::Budget,
+            categorization::Categorizer,
+            forecast::{ForecastOptions, forecast},
+            merge::{MergeOptions, merge_documents},
+            payee::PayeeNormalizer,
+            recurring::{RecurringOptions, detect_recurring},
+            reports::ReportOptions,
+            running_balance::running_balances,
+            transfers::{TransferOptions, match_transfers, transfer_refs},
+            validation::{ValidationIssue, ValidationOptions, validate_statement},
+        },
+    },
+};
+use ratatui::{Terminal, backend::CrosstermBackend};
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use app::{
+    Account, AnnotationField, App, InputField, SourceFile, SourceStatus, Statement, TransactionRow,
+    View,
 };
-use std::{fs, io, path::PathBuf, time::Duration};
 
 #[derive(Parser, Debug)]
 #[command(version, about = "OFX 1.02 transaction viewer in the terminal")]
 struct Args {
-    /// Path to OFX file (v1.02)
-    path: PathBuf,
+    /// OFX files (v1.02), or directories to load every .ofx and .qfx file from
+    #[arg(required_unless_present = "db")]
+    paths: Vec<PathBuf>,
+
+    /// SQLite database to import the files into. Shows everything stored so far.
+    #[arg(long)]
+    db: Option<PathBuf>,
+
+    /// Show every date in this zone (IANA name such as America/Sao_Paulo, or an abbreviation)
+    #[arg(long, value_parser = parse_timezone)]
+    timezone: Option<Tz>,
+
+    /// TOML file with categorization rules and payee aliases
+    #[arg(long)]
+    rules: Option<PathBuf>,
+
+    /// JSON file with notes, tags and splits. Defaults to the OFX file (or database) name with
+    /// `.annotations.json` added, or `annotations.json` in the directory loaded.
+    #[arg(long)]
+    annotations: Option<PathBuf>,
+
+    /// TOML file with monthly budgets per category
+    #[arg(long)]
+    budget: Option<PathBuf>,
+
+    /// Number of days to forecast balances for
+    #[arg(long, default_value_t = 30)]
+    forecast_days: u32,
+
+    /// Work around malformed files, such as SGML elements without end tags, instead of failing
+    #[arg(long)]
+    lenient: bool,
 }
 
-struct App {
-    txs: Vec<Transaction>,
-    state: TableState,
-    scroll: usize,
+/// Why the interface was left.
+enum Exit {
+    Quit,
+    /// Load the files again, parsing them leniently.
+    RetryLenient,
 }
 
-impl App {
-    fn new(txs: Vec<Transaction>) -> Self {
-        let mut s = TableState::default();
-        if !txs.is_empty() {
-            s.select(Some(0));
-        }
-        Self {
-            txs,
-            state: s,
-            scroll: 0,
-        }
-    }
+/// An account the rows were loaded from.
+struct LoadedAccount {
+    key: AccountKey,
+    curdef: String,
+    ledgerbal: Option<Balance>,
+    availbal: Option<Balance>,
+    /// Earliest start and latest end of the account's statements.
+    period: Option<(OfxDateTime, OfxDateTime)>,
+}
 
-    fn selected(&self) -> usize {
-        self.state.selected().unwrap_or(0)
-    }
+/// Rows of every account loaded, along with the accounts themselves.
+type Loaded = (Vec<TransactionRow>, Vec<LoadedAccount>);
 
-    fn select(&mut self, index: usize, viewport: usize) {
-        let max = self.txs.len().saturating_sub(1);
-        let idx = index.min(max);
-        self.state.select(Some(idx));
-        // maintain scroll so selected row stays in view
-        if idx < self.scroll {
-            self.scroll = idx;
-        } else if idx >= self.scroll + viewport {
-            self.scroll = idx + 1 - viewport;
-        }
-    }
+fn parse_timezone(s: &str) -> Result<Tz, String> {
+    resolve_zone(s).ok_or_else(|| format!("unknown timezone: {s}"))
 }
 
 fn main() -> anyhow::Result<()> {
     env_logger::init();
     let args = Args::parse();
-    let content = fs::read_to_string(&args.path)?;
-    let ofx: Vec<Transaction> = OfxParser::parse_string(content.as_ref()).map_or(Vec::new(), |d| {
-        d.body
-            .bank_msgs
-            .iter()
-            .flat_map(|i| {
-                i.stmtrs
-                    .banktranlist
-                    .clone()
-                    .map_or(Vec::new(), |t| t.transactions)
-            })
-            .collect()
-    });
+    let mut app = load(&args, args.lenient)?;
+
+    // Nobody to show the interface to, so just report on the files
+    if !io::stdout().is_terminal() {
+        return report_sources(&app);
+    }
 
     // Setup terminal
     enable_raw_mode()?;
@@ -82,7 +128,16 @@ fn main() -> anyhow::Result<()> {
     execute!(stdout, EnterAlternateScreen)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
-    let res = run_app(&mut terminal, App::new(ofx));
+    let res = loop {
+        match run_app(&mut terminal, app) {
+            Ok(Exit::RetryLenient) => match load(&args, true) {
+                Ok(reloaded) => app = reloaded,
+                Err(e) => break Err(e),
+            },
+            Ok(Exit::Quit) => break Ok(()),
+            Err(e) => break Err(e),
+        }
+    };
 
     // Restore terminal
     disable_raw_mode()?;
@@ -92,124 +147,572 @@ fn main() -> anyhow::Result<()> {
     res
 }
 
-fn run_app(
-    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
-    mut app: App,
-) -> anyhow::Result<()> {
-    loop {
-        terminal.draw(|f| {
-            let area = f.area();
-            let layout = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([
-                    Constraint::Length(1),
-                    Constraint::Min(1),
-                    Constraint::Length(1),
-                ])
-                .split(area);
-
-            // Header
-            let title = format!(
-                "OFX Viewer — {} transactions (q=quit, ↑/↓/j/k=move, g/G=home/end)",
-                app.txs.len()
-            );
-            let header = Block::default()
-                .borders(Borders::BOTTOM)
-                .title(Line::from(title).style(Style::default().add_modifier(Modifier::BOLD)));
-            f.render_widget(header, layout[0]);
-
-            // Table
-            let rows_visible = layout[1].height.saturating_sub(2) as usize; // roughly
-            let start = app.scroll.min(app.txs.len());
-            let end = (start + rows_visible).min(app.txs.len());
-            let visible_rows = app.txs[start..end].iter().map(|t| {
-                Row::new(vec![
-                    Cell::from(t.dtposted.to_string()),
-                    Cell::from(t.trntype.clone()),
-                    Cell::from(format_amount(t.trnamt)),
-                    Cell::from(t.name.clone().unwrap_or("".to_string())),
-                    Cell::from(t.memo.clone().unwrap_or("".to_string())),
-                ])
-            });
-
-            let table = Table::default()
-                .rows(visible_rows)
-                .header(
-                    Row::new(vec!["Date", "Type", "Amount", "Name", "Memo"])
-                        .style(Style::default().add_modifier(Modifier::BOLD))
-                        .bottom_margin(1),
-                )
-                .block(Block::default().borders(Borders::ALL).title("Transactions"))
-                .widths([
-                    Constraint::Length(10), // Date
-                    Constraint::Length(10), // Type
-                    Constraint::Length(12), // Amount
-                    Constraint::Length(30), // Name
-                    Constraint::Min(10),    // Memo
-                ])
-                .row_highlight_style(
-                    Style::default()
-                        .bg(Color::DarkGray)
-                        .add_modifier(Modifier::BOLD),
+/// Parses the files and builds everything the views show from them.
+fn load(args: &Args, lenient: bool) -> anyhow::Result<App> {
+    let options = ParseOptions {
+        timezone: args.timezone,
+        lenient,
+    };
+    let mut sources = Vec::new();
+    let mut documents = Vec::new();
+    for path in statement_files(&args.paths)? {
+        match parse_file(&path, &options) {
+            Ok((document, warnings)) => {
+                let transactions = document
+                    .body
+                    .bank_msgs
+                    .iter()
+                    .flat_map(|m| &m.stmtrs.banktranlist)
+                    .map(|l| l.transactions.len())
+                    .sum();
+                documents.push(document);
+                sources.push(SourceFile {
+                    path,
+                    status: SourceStatus::Loaded {
+                        transactions,
+                        duplicates: 0,
+                        warnings,
+                    },
+                });
+            }
+            Err(status) => sources.push(SourceFile { path, status }),
+        }
+    }
+    let mut details = statement_details(
+        &documents,
+        sources.iter().filter(|s| !s.failed()).map(|s| &s.path),
+    );
+    let loaded: Vec<&mut SourceFile> = sources
+        .iter_mut()
+        .filter(|s| matches!(s.status, SourceStatus::Loaded { .. }))
+        .collect();
+
+    let (mut ofx, accounts): Loaded = match &args.db {
+        Some(db) => load_from_store(db, &documents, loaded, &options)?,
+        None => merge_files(&documents, loaded),
+    };
+
+    for row in ofx.iter_mut() {
+        if let Some((statement, issues)) = details.remove(&row.reference()) {
+            row.statement = Some(statement);
+            row.issues = issues;
+        }
+    }
+
+    let normalizer = match &args.rules {
+        Some(rules) => load_payee_aliases(rules)?,
+        None => PayeeNormalizer::default(),
+    };
+    for row in ofx.iter_mut() {
+        row.payee = normalizer.payee(&row.transaction);
+    }
+
+    let categorizer = match &args.rules {
+        Some(rules) => load_rules(rules)?,
+        None => Categorizer::default(),
+    };
+    for row in ofx.iter_mut() {
+        row.category = categorizer.categorize(&row.transaction, Some(&row.account));
+    }
+
+    let annotations_path = match (&args.annotations, &args.db, args.paths.as_slice()) {
+        (Some(path), _, _) => path.clone(),
+        (None, Some(db), _) => sidecar_path(db),
+        (None, None, [dir]) if dir.is_dir() => dir.join("annotations.json"),
+        (None, None, [path]) => sidecar_path(path),
+        (None, None, _) => PathBuf::from("annotations.json"),
+    };
+    let annotations = load_annotations(&annotations_path)?;
+    let budget = match &args.budget {
+        Some(path) => load_budget(path)?,
+        None => Budget::default(),
+    };
+
+    let transfers = transfer_refs(&match_transfers(
+        ofx.iter().map(|r| (&r.account, &r.transaction)),
+        &TransferOptions::default(),
+    ));
+    for row in ofx.iter_mut() {
+        row.transfer = transfers.contains(&row.reference());
+    }
+
+    let subscriptions = detect_recurring(
+        ofx.iter().map(|r| (&r.account, &r.transaction)),
+        &normalizer,
+        &RecurringOptions::default(),
+    );
+
+    let forecast_options = ForecastOptions {
+        days: args.forecast_days,
+        ..Default::default()
+    };
+    let anomaly_options = AnomalyOptions {
+        normalizer: normalizer.clone(),
+        categorizer: categorizer.clone(),
+        ..Default::default()
+    };
+    let mut tabs: Vec<Account> = Vec::new();
+    for account in accounts {
+        let transactions: Vec<Transaction> = ofx
+            .iter()
+            .filter(|r| r.account == account.key)
+            .map(|r| r.transaction.clone())
+            .collect();
+
+        let anomalies = detect_anomalies(
+            &account.key,
+            &account.curdef,
+            &transactions,
+            &anomaly_options,
+        );
+        for anomaly in anomalies {
+            if let Some(row) = ofx
+                .iter_mut()
+                .find(|r| r.account == account.key && r.transaction.fitid == anomaly.fitid)
+            {
+                row.anomalies = anomaly.reasons;
+            }
+        }
+
+        tabs.push(Account {
+            forecast: account.ledgerbal.as_ref().map(|b| {
+                forecast(
+                    &account.key,
+                    b,
+                    &transactions,
+                    &normalizer,
+                    &forecast_options,
                 )
-                .highlight_symbol(">> ");
+            }),
+            key: account.key,
+            curdef: account.curdef,
+            ledgerbal: account.ledgerbal,
+            availbal: account.availbal,
+            period: account.period,
+        });
+    }
 
-            // We render only the visible slice; highlight is relative to the slice.
-            let mut rel_state = ratatui::widgets::TableState::default();
-            if let Some(sel) = app.state.selected() {
-                if sel >= start && sel < end {
-                    rel_state.select(Some(sel - start));
-                } else {
-                    rel_state.select(None);
+    let report_options = ReportOptions {
+        normalizer,
+        categorizer,
+        annotations: annotations.clone(),
+        ..Default::default()
+    };
+    Ok(App::new(
+        ofx,
+        tabs,
+        subscriptions,
+        report_options,
+        annotations,
+        annotations_path,
+        budget,
+    )
+    .with_sources(sources, lenient))
+}
+
+/// Prints how each file went, failing when any could not be parsed.
+fn report_sources(app: &App) -> anyhow::Result<()> {
+    let mut failed = 0;
+    for source in &app.sources {
+        let path = source.path.display();
+        match &source.status {
+            SourceStatus::Loaded {
+                transactions,
+                duplicates,
+                warnings,
+            } => {
+                println!("{path}: {transactions} transactions, {duplicates} duplicates");
+                for warning in warnings {
+                    eprintln!("{path}: warning: {warning}");
+                }
+            }
+            SourceStatus::Failed { message, .. } => {
+                failed += 1;
+                eprintln!("{path}: error: {message}");
+                for line in source.status.excerpt().into_iter().flatten() {
+                    eprintln!("{line}");
+                }
+            }
+        }
+    }
+    match failed {
+        0 => Ok(()),
+        _ if app.lenient => anyhow::bail!(
+            "{failed} of {} files could not be parsed",
+            app.sources.len()
+        ),
+        _ => anyhow::bail!(
+            "{failed} of {} files could not be parsed, --lenient may get through them",
+            app.sources.len()
+        ),
+    }
+}
+
+/// The files named, with directories replaced by the `.ofx` and `.qfx` files anywhere under
+/// them, sorted by path.
+fn statement_files(paths: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in paths {
+        if !path.is_dir() {
+            files.push(path.clone());
+            continue;
+        }
+        let mut found = Vec::new();
+        let mut pending = vec![path.clone()];
+        while let Some(dir) = pending.pop() {
+            for entry in fs::read_dir(&dir)? {
+                let path = entry?.path();
+                let is_statement = path.extension().is_some_and(|e| {
+                    e.eq_ignore_ascii_case("ofx") || e.eq_ignore_ascii_case("qfx")
+                });
+                if path.is_dir() {
+                    pending.push(path);
+                } else if is_statement {
+                    found.push(path);
                 }
             }
-            f.render_stateful_widget(table, layout[1], &mut rel_state);
+        }
+        found.sort();
+        files.extend(found);
+    }
+    Ok(files)
+}
+
+/// Parses a file, or describes where and why it could not be.
+fn parse_file(
+    path: &Path,
+    options: &ParseOptions,
+) -> Result<(OfxDocument, Vec<ParseWarning>), SourceStatus> {
+    let content = fs::read_to_string(path).map_err(|e| SourceStatus::Failed {
+        message: e.to_string(),
+        location: None,
+        excerpt: None,
+    })?;
+    OfxParser::parse_string_with_warnings(&content, options).map_err(|e| {
+        let location = e.location();
+        SourceStatus::Failed {
+            message: e.to_string(),
+            location,
+            excerpt: location.and_then(|(line, _)| content.lines().nth(line - 1).map(String::from)),
+        }
+    })
+}
+
+/// The statement each transaction in the documents was first read from, along with the issues
+/// validating that statement found about the transaction.
+fn statement_details<'a>(
+    documents: &[OfxDocument],
+    paths: impl Iterator<Item = &'a PathBuf>,
+) -> HashMap<TransactionRef, (Statement, Vec<ValidationIssue>)> {
+    let mut details = HashMap::new();
+    for (document, path) in documents.iter().zip(paths) {
+        for stmtrs in document.body.bank_msgs.iter().map(|m| &m.stmtrs) {
+            let Some(list) = &stmtrs.banktranlist else {
+                continue;
+            };
+            let statement = Statement {
+                path: path.clone(),
+                dtstart: list.dtstart.clone(),
+                dtend: list.dtend.clone(),
+            };
+            let issues = validate_statement(stmtrs, None, &ValidationOptions::default());
+            for transaction in &list.transactions {
+                let reference = TransactionRef {
+                    account: stmtrs.bankacctfrom.key(),
+                    fitid: transaction.fitid.clone(),
+                };
+                details.entry(reference).or_insert_with(|| {
+                    let issues = issues
+                        .iter()
+                        .filter(|i| i.fitid() == Some(transaction.fitid.as_str()))
+                        .cloned()
+                        .collect();
+                    (statement.clone(), issues)
+                });
+            }
+        }
+    }
+    details
+}
 
-            // Footer/instructions
-            let footer = Block::default().borders(Borders::TOP).title("OFX 1.02");
-            f.render_widget(footer, layout[2]);
-        })?;
+/// Merges the documents, counting on each source the transactions dropped as duplicates.
+fn merge_files(documents: &[OfxDocument], mut sources: Vec<&mut SourceFile>) -> Loaded {
+    let result = merge_documents(documents, &MergeOptions::default());
+    for event in &result.merged {
+        if let SourceStatus::Loaded { duplicates, .. } = &mut sources[event.dropped_document].status
+        {
+            *duplicates += 1;
+        }
+    }
+
+    let mut periods: HashMap<AccountKey, (OfxDateTime, OfxDateTime)> = HashMap::new();
+    for stmtrs in documents
+        .iter()
+        .flat_map(|d| &d.body.bank_msgs)
+        .map(|m| &m.stmtrs)
+    {
+        if let Some(list) = &stmtrs.banktranlist {
+            let period = periods
+                .entry(stmtrs.bankacctfrom.key())
+                .or_insert_with(|| (list.dtstart.clone(), list.dtend.clone()));
+            if list.dtstart.datetime < period.0.datetime {
+                period.0 = list.dtstart.clone();
+            }
+            if list.dtend.datetime > period.1.datetime {
+                period.1 = list.dtend.clone();
+            }
+        }
+    }
+
+    let mut rows = Vec::new();
+    let mut accounts = Vec::new();
+    for account in result.accounts {
+        let key = account.account.key();
+        rows.extend(balanced_rows(
+            &key,
+            account.transactions,
+            account.ledgerbal.as_ref(),
+        ));
+        accounts.push(LoadedAccount {
+            period: periods.remove(&key),
+            key,
+            curdef: account.curdef,
+            ledgerbal: account.ledgerbal,
+            availbal: account.availbal,
+        });
+    }
+    (rows, accounts)
+}
+
+/// Imports the documents, then reads every stored account back with its latest balances.
+/// Transactions the store already had count as duplicates on their source.
+fn load_from_store(
+    db: &Path,
+    documents: &[OfxDocument],
+    sources: Vec<&mut SourceFile>,
+    options: &ParseOptions,
+) -> anyhow::Result<Loaded> {
+    let mut store = SqliteStore::open(db)?;
+
+    for (document, source) in documents.iter().zip(sources) {
+        let record = store.import_document(document, &source.path.display().to_string())?;
+        if let SourceStatus::Loaded {
+            transactions,
+            duplicates,
+            ..
+        } = &mut source.status
+        {
+            *transactions = record.added + record.skipped;
+            *duplicates = record.skipped;
+        }
+    }
+
+    let mut rows = Vec::new();
+    let mut accounts = Vec::new();
+    for account in store.accounts()? {
+        let mut transactions = store.effective_transactions(&account.key)?;
+        let mut ledgerbal = store.latest_balance(&account.key, BalanceKind::Ledger)?;
+        let mut availbal = store.latest_balance(&account.key, BalanceKind::Available)?;
+        let mut period = store.statement_period(&account.key)?;
+
+        if let Some(tz) = &options.timezone {
+            for transaction in transactions.iter_mut() {
+                transaction.dtposted = transaction.dtposted.with_timezone(tz);
+            }
+            for balance in [ledgerbal.as_mut(), availbal.as_mut()]
+                .into_iter()
+                .flatten()
+            {
+                balance.dtasof = balance.dtasof.with_timezone(tz);
+            }
+            if let Some((start, end)) = period.as_mut() {
+                *start = start.with_timezone(tz);
+                *end = end.with_timezone(tz);
+            }
+        }
+
+        rows.extend(balanced_rows(
+            &account.key,
+            transactions,
+            ledgerbal.as_ref(),
+        ));
+        accounts.push(LoadedAccount {
+            key: account.key,
+            curdef: account.curdef,
+            ledgerbal,
+            availbal,
+            period,
+        });
+    }
+    Ok((rows, accounts))
+}
+
+fn balanced_rows(
+    account: &AccountKey,
+    transactions: Vec<Transaction>,
+    anchor: Option<&Balance>,
+) -> Vec<TransactionRow> {
+    let row = |transaction, balance| TransactionRow {
+        account: account.clone(),
+        transaction,
+        payee: String::new(),
+        balance,
+        category: None,
+        transfer: false,
+        anomalies: Vec::new(),
+        statement: None,
+        issues: Vec::new(),
+    };
+
+    match anchor {
+        Some(anchor) => running_balances(&transactions, anchor)
+            .into_iter()
+            .map(|b| row(b.transaction, Some(b.balance)))
+            .collect(),
+        None => transactions.into_iter().map(|t| row(t, None)).collect(),
+    }
+}
+
+fn run_app(
+    terminal: &mut Terminal<CrosstermBackend<std::io::Stdout>>,
+    mut app: App,
+) -> anyhow::Result<Exit> {
+    loop {
+        terminal.draw(|f| ui::draw(f, &mut app))?;
 
         // Input handling
-        if crossterm::event::poll(Duration::from_millis(200))? {
-            if let Event::Key(key) = event::read()? {
-                // Ignore key repeats on some terminals
-                if key.kind == KeyEventKind::Release {
-                    continue;
-                }
+        if crossterm::event::poll(Duration::from_millis(200))?
+            && let Event::Key(key) = event::read()?
+        {
+            // Ignore key repeats on some terminals
+            if key.kind == KeyEventKind::Release {
+                continue;
+            }
+            app.status = None;
+            let viewport = terminal.size()?.height.saturating_sub(3) as usize;
+
+            if let Some(input) = app.input.as_mut() {
                 match key.code {
-                    KeyCode::Char('q') => return Ok(()),
-                    KeyCode::Down | KeyCode::Char('j') => {
-                        let sel = app.selected().saturating_add(1);
-                        let viewport = terminal.size()?.height.saturating_sub(3) as usize;
-                        app.select(sel, viewport);
-                    }
-                    KeyCode::Up | KeyCode::Char('k') => {
-                        let sel = app.selected().saturating_sub(1);
-                        let viewport = terminal.size()?.height.saturating_sub(3) as usize;
-                        app.select(sel, viewport);
+                    KeyCode::Enter => app.finish_editing(),
+                    KeyCode::Esc => {
+                        if input.field == InputField::Search {
+                            app.search = None;
+                        }
+                        app.input = None;
                     }
-                    KeyCode::Char('g') => {
-                        let viewport = terminal.size()?.height.saturating_sub(3) as usize;
-                        app.select(0, viewport);
+                    KeyCode::Backspace => {
+                        input.buffer.pop();
+                        app.update_search(viewport);
                     }
-                    KeyCode::Char('G') => {
-                        if !app.txs.is_empty() {
-                            let viewport = terminal.size()?.height.saturating_sub(3) as usize;
-                            app.select(app.txs.len() - 1, viewport);
-                        }
+                    KeyCode::Char(c) => {
+                        input.buffer.push(c);
+                        app.update_search(viewport);
                     }
                     _ => {}
                 }
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Char('q') => return Ok(Exit::Quit),
+                KeyCode::Char('1') => app.view = View::Transactions,
+                KeyCode::Char('2') => app.view = View::Subscriptions,
+                KeyCode::Char('3') => app.view = View::Reports,
+                KeyCode::Char('4') => app.view = View::Budgets,
+                KeyCode::Char('5') => app.view = View::Forecast,
+                KeyCode::Char('!') if !app.sources.is_empty() => app.view = View::Errors,
+                KeyCode::Char('r') if app.view == View::Errors && !app.lenient => {
+                    return Ok(Exit::RetryLenient);
+                }
+                KeyCode::Left | KeyCode::Char('h') if app.view == View::Budgets => {
+                    app.shift_budget_month(-1)
+                }
+                KeyCode::Right | KeyCode::Char('l') if app.view == View::Budgets => {
+                    app.shift_budget_month(1)
+                }
+                KeyCode::Left | KeyCode::Char('h') if app.view == View::Forecast => {
+                    app.cycle_forecast_account(-1)
+                }
+                KeyCode::Right | KeyCode::Char('l') if app.view == View::Forecast => {
+                    app.cycle_forecast_account(1)
+                }
+                KeyCode::Tab if app.view == View::Transactions => app.cycle_account_tab(1),
+                KeyCode::BackTab if app.view == View::Transactions => app.cycle_account_tab(-1),
+                KeyCode::Char('p') if app.view == View::Reports => app.cycle_report_period(),
+                KeyCode::Char('b') if app.view == View::Reports => app.cycle_report_grouping(),
+                KeyCode::Char('e') if app.view == View::Reports => app.export_report(),
+                KeyCode::Char('/') if app.view == View::Transactions => app.start_search(),
+                KeyCode::Char('n') if app.view == View::Transactions => app.next_match(1, viewport),
+                KeyCode::Char('N') if app.view == View::Transactions => {
+                    app.next_match(-1, viewport)
+                }
+                KeyCode::Enter if app.view == View::Transactions => app.detail = !app.detail,
+                KeyCode::Esc if app.view == View::Transactions && app.detail => app.detail = false,
+                KeyCode::Esc if app.view == View::Transactions => app.search = None,
+                KeyCode::Char('f') if app.view == View::Transactions => app.start_filter(),
+                KeyCode::Char('o') if app.view == View::Transactions => app.cycle_sort_column(),
+                KeyCode::Char('O') if app.view == View::Transactions => app.toggle_sort_direction(),
+                KeyCode::Char('e') if app.view == View::Transactions => {
+                    app.start_editing(AnnotationField::Notes)
+                }
+                KeyCode::Char('t') if app.view == View::Transactions => {
+                    app.start_editing(AnnotationField::Tags)
+                }
+                KeyCode::Char('s') if app.view == View::Transactions => {
+                    app.start_editing(AnnotationField::Splits)
+                }
+                KeyCode::Down | KeyCode::Char('j') => {
+                    let sel = app.selected_in_view().saturating_add(1);
+                    app.select_in_view(sel, viewport);
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    let sel = app.selected_in_view().saturating_sub(1);
+                    app.select_in_view(sel, viewport);
+                }
+                KeyCode::Char('g') => {
+                    app.select_in_view(0, viewport);
+                }
+                KeyCode::Char('G') if app.len() > 0 => {
+                    app.select_in_view(app.len() - 1, viewport);
+                }
+                _ => {}
             }
         }
     }
 }
 
-fn format_amount(a: f64) -> String {
-    if a < 0.0 {
-        format!("-${:.2}", -a)
-    } else {
-        format!(" ${a:.2}")
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn finds_statements_anywhere_under_a_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("2025/06")).unwrap();
+        for name in [
+            "b.ofx",
+            "a.QFX",
+            "notes.txt",
+            "2025/06/june.Ofx",
+            "2025/statement.ofx.bak",
+        ] {
+            fs::write(root.join(name), "").unwrap();
+        }
+        let named = PathBuf::from("elsewhere/named.txt");
+
+        let files = statement_files(&[root.to_path_buf(), named.clone()]).unwrap();
+
+        assert_eq!(
+            files,
+            vec![
+                root.join("2025/06/june.Ofx"),
+                root.join("a.QFX"),
+                root.join("b.ofx"),
+                named,
+            ]
+        );
     }
 }
@@ -0,0 +1,1050 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    symbols::Marker,
+    text::{Line, Span},
+    widgets::{
+        Axis, Block, Borders, Cell, Chart, Clear, Dataset, GraphType, Paragraph, Row, Table,
+        TableState, Tabs, Wrap,
+    },
+};
+
+use ofx_parser::domain::entities::ofx::{AccountKey, Balance};
+
+use crate::app::{App, InputField, SourceStatus, TransactionRow, View};
+
+pub fn draw(f: &mut Frame, app: &mut App) {
+    let area = f.area();
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .split(area);
+
+    // Header
+    let title = format!(
+        "OFX Viewer — {} transactions (q=quit, ↑/↓/j/k=move, g/G=home/end, Tab=account, Enter=details, /=search, n/N=next/previous match, f=filter, o/O=sort column/direction, e/t/s=notes/tags/splits, 1=transactions, 2=subscriptions, 3=reports, 4=budgets, 5=forecast, !=errors)",
+        app.txs.len()
+    );
+    let header = Block::default()
+        .borders(Borders::BOTTOM)
+        .title(Line::from(title).style(Style::default().add_modifier(Modifier::BOLD)));
+    f.render_widget(header, layout[0]);
+
+    let body = if app.sources.is_empty() {
+        layout[1]
+    } else {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(34), Constraint::Min(1)])
+            .split(layout[1]);
+        draw_sources(f, app, columns[0]);
+        columns[1]
+    };
+
+    match app.view {
+        View::Transactions => draw_transactions(f, app, body),
+        View::Subscriptions => draw_subscriptions(f, app, body),
+        View::Reports => draw_reports(f, app, body),
+        View::Budgets => draw_budgets(f, app, body),
+        View::Forecast => draw_forecast(f, app, body),
+        View::Errors => draw_errors(f, app, body),
+    }
+    if app.view == View::Transactions && app.detail {
+        draw_detail(f, app, body);
+    }
+
+    // Footer/instructions
+    let footer = Block::default()
+        .borders(Borders::TOP)
+        .title(format!("OFX 1.02{}", footer_details(app)));
+    f.render_widget(footer, layout[2]);
+}
+
+/// Sidebar listing the files loaded, each with what came of parsing it.
+fn draw_sources(f: &mut Frame, app: &App, area: Rect) {
+    let mut lines = Vec::new();
+    for source in &app.sources {
+        let name = source
+            .path
+            .file_name()
+            .map_or(source.path.display().to_string(), |n| {
+                n.to_string_lossy().into_owned()
+            });
+        let (mark, detail, color) = match &source.status {
+            SourceStatus::Loaded {
+                transactions,
+                duplicates,
+                warnings,
+            } => {
+                let mut detail = format!("{transactions} transactions");
+                if *duplicates > 0 {
+                    detail += &format!(", {duplicates} duplicates");
+                }
+                match warnings.len() {
+                    0 => ("✓", detail, Color::Green),
+                    n => ("!", format!("{detail}, {n} warnings"), Color::Yellow),
+                }
+            }
+            SourceStatus::Failed { message, .. } => ("✗", message.clone(), Color::Red),
+        };
+        lines.push(Line::from(format!("{mark} {name}")).style(Style::default().fg(color)));
+        lines.push(Line::from(format!("  {detail}")).style(Style::default().fg(Color::DarkGray)));
+    }
+
+    let failed = app.sources.iter().filter(|s| s.failed()).count();
+    let title = match failed {
+        0 => format!("Files ({})", app.sources.len()),
+        _ => format!("Files ({}, {failed} failed)", app.sources.len()),
+    };
+    let sidebar = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .wrap(Wrap { trim: false });
+    f.render_widget(sidebar, area);
+}
+
+/// Every file that failed to parse or needed lenient parsing, with what went wrong where.
+fn draw_errors(f: &mut Frame, app: &mut App, area: Rect) {
+    let problems = app.problems();
+    let failed = problems.iter().filter(|s| s.failed()).count();
+    let rows: Vec<Row> = problems
+        .iter()
+        .map(|source| {
+            let lines: Vec<Line> = match &source.status {
+                SourceStatus::Failed { message, .. } => std::iter::once(
+                    Line::from(message.clone()).style(Style::default().fg(Color::Red)),
+                )
+                .chain(
+                    source
+                        .status
+                        .excerpt()
+                        .into_iter()
+                        .flatten()
+                        .map(Line::from),
+                )
+                .collect(),
+                SourceStatus::Loaded { warnings, .. } => warnings
+                    .iter()
+                    .map(|w| Line::from(w.to_string()).style(Style::default().fg(Color::Yellow)))
+                    .collect(),
+            };
+            let height = lines.len() as u16;
+            Row::new(vec![
+                Cell::from(source.path.display().to_string()),
+                Cell::from(lines),
+            ])
+            .height(height)
+        })
+        .collect();
+
+    let mut title = format!(
+        "Errors ({failed} failed, {} with warnings)",
+        problems.len() - failed
+    );
+    if failed > 0 && !app.lenient {
+        title += " — r=retry with lenient parsing";
+    }
+    let table = Table::new(rows, [Constraint::Percentage(30), Constraint::Min(20)])
+        .header(
+            Row::new(vec!["File", "Problem"])
+                .style(Style::default().add_modifier(Modifier::BOLD))
+                .bottom_margin(1),
+        )
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .row_highlight_style(highlight_style())
+        .highlight_symbol(">> ");
+
+    f.render_stateful_widget(table, area, &mut app.errors_state);
+}
+
+fn draw_transactions(f: &mut Frame, app: &mut App, area: Rect) {
+    let summary = summary_lines(app);
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(summary.len() as u16 + 2),
+            Constraint::Min(1),
+        ])
+        .split(area);
+
+    let titles = std::iter::once("All accounts".to_string()).chain(app.accounts.iter().map(|a| {
+        format!(
+            "{} {} {} {}",
+            a.key.bankid,
+            mask_acctid(&a.key.acctid),
+            a.key.accttype,
+            a.curdef
+        )
+    }));
+    let tabs = Tabs::new(titles)
+        .select(app.account_tab)
+        .highlight_style(highlight_style());
+    f.render_widget(tabs, layout[0]);
+    let summary =
+        Paragraph::new(summary).block(Block::default().borders(Borders::ALL).title("Summary"));
+    f.render_widget(summary, layout[1]);
+    let area = layout[2];
+
+    let all_accounts = app.current_account().is_none();
+    let search = app.search.as_deref();
+    let rows_visible = area.height.saturating_sub(2) as usize; // roughly
+    // Keep the selection in view when filtering or sorting moved it
+    if let Some(sel) = app.state.selected() {
+        if sel < app.scroll {
+            app.scroll = sel;
+        } else if sel >= app.scroll + rows_visible {
+            app.scroll = sel + 1 - rows_visible;
+        }
+    }
+    let app = &*app;
+    let start = app.scroll.min(app.visible.len());
+    let end = (start + rows_visible).min(app.visible.len());
+    let visible_rows = app.visible[start..end].iter().map(|&i| {
+        let r = &app.txs[i];
+        let t = &r.transaction;
+        let account = all_accounts.then(|| Cell::from(account_label(&r.account)));
+        let style = if r.anomalies.is_empty() {
+            Style::default()
+        } else {
+            Style::default().fg(Color::LightRed)
+        };
+        Row::new(account.into_iter().chain([
+            Cell::from(t.dtposted.to_string()),
+            highlighted(t.trntype.clone(), search),
+            highlighted(format_amount(t.trnamt), search),
+            Cell::from(r.balance.map_or(String::new(), format_amount)),
+            Cell::from(category_label(app, r)),
+            highlighted(r.payee.clone(), search),
+            highlighted(t.memo.clone().unwrap_or("".to_string()), search),
+        ]))
+        .style(style)
+    });
+    let flagged = app
+        .visible
+        .iter()
+        .filter(|&&i| !app.txs[i].anomalies.is_empty())
+        .count();
+    let title = if flagged == 0 {
+        "Transactions".to_string()
+    } else {
+        format!("Transactions ({flagged} flagged)")
+    };
+
+    let table = Table::default()
+        .rows(visible_rows)
+        .header(
+            Row::new(
+                all_accounts
+                    .then(|| "Account".to_string())
+                    .into_iter()
+                    .chain(
+                        [
+                            "Date", "Type", "Amount", "Balance", "Category", "Payee", "Memo",
+                        ]
+                        .map(|h| match app.sort {
+                            Some(sort) if sort.column.header() == h => {
+                                format!("{h} {}", if sort.descending { "▼" } else { "▲" })
+                            }
+                            _ => h.to_string(),
+                        }),
+                    ),
+            )
+            .style(Style::default().add_modifier(Modifier::BOLD))
+            .bottom_margin(1),
+        )
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .widths(
+            all_accounts
+                .then_some(Constraint::Length(20))
+                .into_iter()
+                .chain([
+                    Constraint::Length(10), // Date
+                    Constraint::Length(10), // Type
+                    Constraint::Length(12), // Amount
+                    Constraint::Length(12), // Balance
+                    Constraint::Length(16), // Category
+                    Constraint::Length(30), // Payee
+                    Constraint::Min(10),    // Memo
+                ]),
+        )
+        .row_highlight_style(highlight_style())
+        .highlight_symbol(">> ");
+
+    // We render only the visible slice; highlight is relative to the slice.
+    let mut rel_state = TableState::default();
+    if let Some(sel) = app.state.selected() {
+        if sel >= start && sel < end {
+            rel_state.select(Some(sel - start));
+        } else {
+            rel_state.select(None);
+        }
+    }
+    f.render_stateful_widget(table, area, &mut rel_state);
+}
+
+/// Statement period and totals of the rows on screen, followed by the statement balances of
+/// the account on the selected tab, or of every account on the all accounts one.
+fn summary_lines(app: &App) -> Vec<Line<'static>> {
+    let summary = app.summary();
+    let period = match summary.statement_period {
+        Some((start, end)) => format!("Statements {start} to {end}"),
+        None => "Statement period unknown".to_string(),
+    };
+    let mut transactions = format!("{} transactions", summary.transactions);
+    match summary.posted {
+        Some((first, last)) if summary.filtered => {
+            transactions.push_str(&format!(" (filtered, {first} to {last})"));
+        }
+        _ if summary.filtered => transactions.push_str(" (filtered)"),
+        _ => {}
+    }
+    let separator = || Span::styled(" │ ", Style::default().fg(Color::DarkGray));
+    let mut lines = vec![Line::from(vec![
+        Span::raw(period),
+        separator(),
+        Span::raw(transactions),
+        separator(),
+        Span::styled(
+            format!("In {}", format_amount(summary.inflow).trim_start()),
+            Style::default().fg(Color::Green),
+        ),
+        separator(),
+        Span::styled(
+            format!("Out {}", format_amount(summary.outflow).trim_start()),
+            Style::default().fg(Color::Red),
+        ),
+        separator(),
+        Span::styled(
+            format!("Net {}", format_amount(summary.net).trim_start()),
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+    ])];
+
+    let all_accounts = app.current_account().is_none();
+    for account in app.tab_accounts() {
+        let balance = |label: &str, balance: &Option<Balance>| {
+            balance.as_ref().map(|b| {
+                format!(
+                    "{label} {} {} as of {}",
+                    format_amount(b.balamt).trim_start(),
+                    account.curdef,
+                    b.dtasof.datetime.date_naive()
+                )
+            })
+        };
+        let balances: Vec<String> = [
+            balance("Statement ledger", &account.ledgerbal),
+            balance("Statement available", &account.availbal),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if balances.is_empty() {
+            continue;
+        }
+        let prefix = if all_accounts {
+            format!("{}: ", account_label(&account.key))
+        } else {
+            String::new()
+        };
+        lines.push(Line::from(format!("{prefix}{}", balances.join(" │ "))));
+    }
+    lines
+}
+
+/// Every field of the selected transaction, over the middle of the table.
+fn draw_detail(f: &mut Frame, app: &App, area: Rect) {
+    let Some(row) = app.selected_row() else {
+        return;
+    };
+    let t = &row.transaction;
+    let curdef = app
+        .accounts
+        .iter()
+        .find(|a| a.key == row.account)
+        .map_or("", |a| a.curdef.as_str());
+
+    let mut fields: Vec<(&str, String)> = vec![(
+        "Account",
+        format!(
+            "{} {} {} {curdef}",
+            row.account.bankid,
+            mask_acctid(&row.account.acctid),
+            row.account.accttype
+        ),
+    )];
+    if let Some(statement) = &row.statement {
+        fields.push((
+            "Statement",
+            format!(
+                "{} ({} to {})",
+                statement.path.display(),
+                statement.dtstart.datetime.date_naive(),
+                statement.dtend.datetime.date_naive()
+            ),
+        ));
+    }
+    fields.extend([
+        ("FITID", t.fitid.clone()),
+        ("Type", t.trntype.clone()),
+        ("Amount", format!("{} {curdef}", format_amount(t.trnamt))),
+    ]);
+    for (label, currency) in [("Currency", &t.currency), ("Original", &t.origcurrency)] {
+        if let Some(c) = currency {
+            fields.push((label, format!("{} at {}", c.cursym, c.currate)));
+        }
+    }
+    if let Some(balance) = row.balance {
+        fields.push(("Balance", format_amount(balance)));
+    }
+    fields.push((
+        "Posted",
+        t.dtposted.raw.clone().unwrap_or_else(|| "—".to_string()),
+    ));
+    fields.push((
+        "Parsed",
+        format!(
+            "{}{}",
+            t.dtposted.datetime.format("%Y-%m-%d %H:%M:%S %:z"),
+            t.dtposted
+                .tz_name
+                .as_ref()
+                .map_or(String::new(), |n| format!(" ({n})"))
+        ),
+    ));
+    fields.extend([
+        ("Name", t.name.clone().unwrap_or_default()),
+        ("Payee", row.payee.clone()),
+        ("Memo", t.memo.clone().unwrap_or_default()),
+    ]);
+    if let Some(correctfitid) = &t.correctfitid {
+        let action = t
+            .correctaction
+            .map_or(String::new(), |a| format!(" ({a:?})"));
+        fields.push(("Corrects", format!("{correctfitid}{action}")));
+    }
+    if let Some(category) = &row.category {
+        fields.push((
+            "Category",
+            format!("{} (rule {})", category.category, category.rule),
+        ));
+    }
+    if row.transfer {
+        fields.push((
+            "Transfer",
+            "Matched with another loaded account".to_string(),
+        ));
+    }
+    if let Some(annotation) = app.annotation(row) {
+        let tags: Vec<&str> = row
+            .category
+            .iter()
+            .flat_map(|c| &c.tags)
+            .chain(&annotation.tags)
+            .map(String::as_str)
+            .collect();
+        if !tags.is_empty() {
+            fields.push(("Tags", tags.join(", ")));
+        }
+        if let Some(notes) = &annotation.notes {
+            fields.push(("Notes", notes.clone()));
+        }
+        for split in &annotation.splits {
+            fields.push((
+                "Split",
+                format!("{} {}", split.category, format_amount(split.amount)),
+            ));
+        }
+    }
+    fields.extend(row.anomalies.iter().map(|a| ("Unusual", a.to_string())));
+    fields.extend(row.issues.iter().map(|i| ("Validation", i.to_string())));
+    fields.extend(
+        t.unknown
+            .iter()
+            .map(|u| (u.tag.as_str(), u.value.clone().unwrap_or_default())),
+    );
+
+    let lines: Vec<Line> = fields
+        .into_iter()
+        .map(|(label, value)| {
+            let style = match label {
+                "Unusual" => Style::default().fg(Color::LightRed),
+                "Validation" => Style::default().fg(Color::Yellow),
+                _ => Style::default(),
+            };
+            Line::from(vec![
+                Span::styled(
+                    format!("{label:<12}"),
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(value, style),
+            ])
+        })
+        .collect();
+
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let width = area.width.saturating_sub(8).min(100);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+    let detail = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Transaction (Enter/Esc=close)"),
+        )
+        .wrap(Wrap { trim: false });
+    f.render_widget(Clear, popup);
+    f.render_widget(detail, popup);
+}
+
+fn draw_subscriptions(f: &mut Frame, app: &mut App, area: Rect) {
+    let rows = app.subscriptions.iter().map(|s| {
+        let mut notes = Vec::new();
+        if !s.missed.is_empty() {
+            notes.push(format!("{} missed", s.missed.len()));
+        }
+        if let Some(change) = s.price_changes.last() {
+            notes.push(format!(
+                "price {} → {} on {}",
+                format_amount(change.previous),
+                format_amount(change.amount),
+                change.date
+            ));
+        }
+
+        let style = if s.missed.is_empty() {
+            Style::default()
+        } else {
+            Style::default().fg(Color::Yellow)
+        };
+        Row::new(vec![
+            Cell::from(account_label(&s.account)),
+            Cell::from(s.payee.clone()),
+            Cell::from(s.cadence.to_string()),
+            Cell::from(format_amount(s.expected_amount)),
+            Cell::from(s.occurrences.len().to_string()),
+            Cell::from(s.last_date().to_string()),
+            Cell::from(s.expected_next_date.to_string()),
+            Cell::from(notes.join(", ")),
+        ])
+        .style(style)
+    });
+
+    let table = Table::default()
+        .rows(rows)
+        .header(
+            Row::new(vec![
+                "Account", "Payee", "Cadence", "Amount", "Count", "Last", "Next", "Notes",
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD))
+            .bottom_margin(1),
+        )
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Subscriptions and recurring transactions"),
+        )
+        .widths([
+            Constraint::Length(24), // Account
+            Constraint::Length(30), // Payee
+            Constraint::Length(8),  // Cadence
+            Constraint::Length(12), // Amount
+            Constraint::Length(5),  // Count
+            Constraint::Length(10), // Last
+            Constraint::Length(10), // Next
+            Constraint::Min(10),    // Notes
+        ])
+        .row_highlight_style(highlight_style())
+        .highlight_symbol(">> ");
+
+    f.render_stateful_widget(table, area, &mut app.subscriptions_state);
+}
+
+fn draw_reports(f: &mut Frame, app: &mut App, area: Rect) {
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(78), Constraint::Min(30)])
+        .split(area);
+
+    let (income, expense, net) = app.report.averages();
+    let average = Row::new(vec![
+        Cell::from("Average"),
+        Cell::from(format_amount(income)),
+        Cell::from(format_amount(-expense)),
+        Cell::from(format_amount(net)),
+        Cell::from(""),
+        Cell::from(""),
+    ])
+    .style(Style::default().add_modifier(Modifier::ITALIC));
+    let rows = app
+        .report
+        .periods
+        .iter()
+        .map(|p| {
+            Row::new(vec![
+                Cell::from(p.label.clone()),
+                Cell::from(format_amount(p.totals.income)),
+                Cell::from(format_amount(-p.totals.expense)),
+                Cell::from(format_amount(p.totals.net())),
+                Cell::from(p.totals.count.to_string()),
+                Cell::from(format_amount(p.transfers.income)),
+            ])
+        })
+        .chain(std::iter::once(average));
+
+    let periods = Table::default()
+        .rows(rows)
+        .header(
+            Row::new(vec![
+                "Period",
+                "Income",
+                "Expense",
+                "Net",
+                "Count",
+                "Transfers",
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD))
+            .bottom_margin(1),
+        )
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "{} report (p=period, b=group by, e=export)",
+            app.report.period
+        )))
+        .widths([
+            Constraint::Length(10), // Period
+            Constraint::Length(13), // Income
+            Constraint::Length(13), // Expense
+            Constraint::Length(13), // Net
+            Constraint::Length(6),  // Count
+            Constraint::Length(13), // Transfers
+        ])
+        .row_highlight_style(highlight_style())
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(periods, columns[0], &mut app.reports_state);
+
+    let selected = app.report.periods.get(app.selected_in_view());
+    let rows = selected
+        .into_iter()
+        .flat_map(|p| &p.groups)
+        .map(|(group, totals)| {
+            let trend = app.report.trends.iter().find(|t| &t.group == group);
+            Row::new(vec![
+                Cell::from(group.clone()),
+                Cell::from(format_amount(totals.income)),
+                Cell::from(format_amount(-totals.expense)),
+                Cell::from(totals.count.to_string()),
+                Cell::from(trend.map_or(String::new(), |t| format_amount(t.average))),
+                Cell::from(
+                    trend
+                        .and_then(|t| t.change)
+                        .map_or(String::new(), |c| format!("{:+.0}%", c * 100.0)),
+                ),
+            ])
+        });
+
+    let groups = Table::default()
+        .rows(rows)
+        .header(
+            Row::new(vec![
+                "Group", "Income", "Expense", "Count", "Average", "Trend",
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD))
+            .bottom_margin(1),
+        )
+        .block(Block::default().borders(Borders::ALL).title(format!(
+            "{} by {}",
+            selected.map_or("", |p| p.label.as_str()),
+            app.report.group_by
+        )))
+        .widths([
+            Constraint::Min(16),    // Group
+            Constraint::Length(13), // Income
+            Constraint::Length(13), // Expense
+            Constraint::Length(6),  // Count
+            Constraint::Length(13), // Average
+            Constraint::Length(7),  // Trend
+        ]);
+    f.render_widget(groups, columns[1]);
+}
+
+fn draw_budgets(f: &mut Frame, app: &mut App, area: Rect) {
+    let Some(month) = app.current_budget() else {
+        let message = if app.budget.categories.is_empty() {
+            "No budget loaded, start with --budget budget.toml"
+        } else {
+            "No transactions to compare the budget against"
+        };
+        f.render_widget(Block::default().borders(Borders::ALL).title(message), area);
+        return;
+    };
+
+    let rows: Vec<Row> = month
+        .lines
+        .iter()
+        .map(|line| {
+            let used = line.used();
+            let color = if used > 1.0 {
+                Color::Red
+            } else if used > 0.8 {
+                Color::Yellow
+            } else {
+                Color::Green
+            };
+            Row::new(vec![
+                Cell::from(line.category.clone()),
+                Cell::from(format_amount(line.limit)),
+                Cell::from(format_amount(line.carried)),
+                Cell::from(format_amount(line.spent)),
+                Cell::from(format_amount(line.remaining())),
+                Cell::from(progress_bar(used, 30)).style(Style::default().fg(color)),
+            ])
+        })
+        .collect();
+
+    let title = format!(
+        "Budget {} ({}/{}, h/l=month) — unbudgeted spending {}",
+        month.month.format("%Y-%m"),
+        app.budget_month + 1,
+        app.budgets.len(),
+        format_amount(month.unbudgeted)
+    );
+    let table = Table::default()
+        .rows(rows)
+        .header(
+            Row::new(vec![
+                "Category",
+                "Budget",
+                "Carried",
+                "Spent",
+                "Remaining",
+                "Used",
+            ])
+            .style(Style::default().add_modifier(Modifier::BOLD))
+            .bottom_margin(1),
+        )
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .widths([
+            Constraint::Length(20), // Category
+            Constraint::Length(12), // Budget
+            Constraint::Length(12), // Carried
+            Constraint::Length(12), // Spent
+            Constraint::Length(12), // Remaining
+            Constraint::Min(20),    // Used
+        ])
+        .row_highlight_style(highlight_style())
+        .highlight_symbol(">> ");
+
+    f.render_stateful_widget(table, area, &mut app.budgets_state);
+}
+
+fn draw_forecast(f: &mut Frame, app: &mut App, area: Rect) {
+    let Some((account, forecast)) = app.current_forecast() else {
+        f.render_widget(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("No account with a ledger balance to forecast from"),
+            area,
+        );
+        return;
+    };
+
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(55), Constraint::Min(5)])
+        .split(area);
+
+    let points: Vec<(f64, f64)> = std::iter::once((0.0, forecast.opening_balance))
+        .chain(
+            forecast
+                .days
+                .iter()
+                .enumerate()
+                .map(|(i, d)| ((i + 1) as f64, d.balance)),
+        )
+        .collect();
+    let last_day = forecast.days.len() as f64;
+    let zero = [(0.0, 0.0), (last_day, 0.0)];
+    let (low, high) = points
+        .iter()
+        .fold((0.0_f64, 0.0_f64), |(low, high), &(_, y)| {
+            (low.min(y), high.max(y))
+        });
+    let margin = ((high - low) * 0.05).max(1.0);
+    let (low, high) = (low - margin, high + margin);
+
+    let mut title = format!(
+        "Forecast {}/{} {} from {} ({}/{}, h/l=account)",
+        account.bankid,
+        account.acctid,
+        account.accttype,
+        format_amount(forecast.opening_balance),
+        app.forecast_account + 1,
+        app.forecasts().count()
+    );
+    let mut title_style = Style::default();
+    if let Some(day) = forecast.first_negative() {
+        title.push_str(&format!(
+            " — goes negative on {} ({})",
+            day.date,
+            format_amount(day.balance)
+        ));
+        title_style = title_style.fg(Color::Red).add_modifier(Modifier::BOLD);
+    }
+
+    let end = forecast.days.last().map_or(forecast.start, |d| d.date);
+    let chart = Chart::new(vec![
+        Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::DarkGray))
+            .data(&zero),
+        Dataset::default()
+            .name("Balance")
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(Color::Cyan))
+            .data(&points),
+    ])
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(Line::from(title).style(title_style)),
+    )
+    .x_axis(
+        Axis::default()
+            .bounds([0.0, last_day.max(1.0)])
+            .labels([forecast.start.to_string(), end.to_string()]),
+    )
+    .y_axis(
+        Axis::default()
+            .bounds([low, high])
+            .labels([format_amount(low), format_amount(high)]),
+    );
+    f.render_widget(chart, layout[0]);
+
+    let rows: Vec<Row> = app
+        .forecast_days()
+        .into_iter()
+        .map(|day| {
+            let transactions = day
+                .transactions
+                .iter()
+                .map(|t| format!("{} {} ({})", t.payee, format_amount(t.amount), t.cadence))
+                .collect::<Vec<_>>()
+                .join("; ");
+            let balance_style = if day.balance < 0.0 {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default()
+            };
+            Row::new(vec![
+                Cell::from(day.date.to_string()),
+                Cell::from(format_amount(day.balance)).style(balance_style),
+                Cell::from(transactions),
+            ])
+        })
+        .collect();
+
+    let table = Table::default()
+        .rows(rows)
+        .header(
+            Row::new(vec!["Date", "Balance", "Projected transactions"])
+                .style(Style::default().add_modifier(Modifier::BOLD))
+                .bottom_margin(1),
+        )
+        .block(Block::default().borders(Borders::ALL))
+        .widths([
+            Constraint::Length(12), // Date
+            Constraint::Length(12), // Balance
+            Constraint::Min(20),    // Projected transactions
+        ])
+        .row_highlight_style(highlight_style())
+        .highlight_symbol(">> ");
+
+    f.render_stateful_widget(table, layout[1], &mut app.forecast_state);
+}
+
+/// `███████░░░ 70%`, full once over budget.
+fn progress_bar(used: f64, width: usize) -> String {
+    let filled = ((used.clamp(0.0, 1.0) * width as f64).round() as usize).min(width);
+    let percent = if used.is_finite() {
+        format!("{:.0}%", used * 100.0)
+    } else {
+        "over".to_string()
+    };
+    format!(
+        "{}{} {percent}",
+        "█".repeat(filled),
+        "░".repeat(width - filled)
+    )
+}
+
+fn footer_details(app: &App) -> String {
+    if let Some(input) = &app.input {
+        let error = app
+            .status
+            .as_ref()
+            .map_or(String::new(), |s| format!(" — {s}"));
+        return match input.field {
+            InputField::Search => format!(
+                " — /{}▏ ({} matches, Enter=keep, Esc=cancel)",
+                input.buffer,
+                search_matches(app)
+            ),
+            field => format!(
+                " — {}: {}▏ (Enter=save, Esc=cancel){error}",
+                field.prompt(),
+                input.buffer
+            ),
+        };
+    }
+    if let Some(status) = &app.status {
+        return format!(" — {status}");
+    }
+
+    match app.view {
+        View::Transactions => {
+            let selected = app.selected_row();
+            let raw_name = selected
+                .and_then(|r| r.transaction.name.as_ref())
+                .map_or(String::new(), |n| format!(" — {n}"));
+            let rule = selected
+                .and_then(|r| r.category.as_ref())
+                .map_or(String::new(), |c| format!(" — rule: {}", c.rule));
+            let annotation = selected.and_then(|r| app.annotation(r));
+            let tags: Vec<&str> = selected
+                .and_then(|r| r.category.as_ref())
+                .into_iter()
+                .flat_map(|c| &c.tags)
+                .chain(annotation.into_iter().flat_map(|a| &a.tags))
+                .map(String::as_str)
+                .collect();
+            let tags = if tags.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", tags.join(", "))
+            };
+            let notes = annotation
+                .and_then(|a| a.notes.as_ref())
+                .map_or(String::new(), |n| format!(" — {n}"));
+            let anomalies =
+                selected
+                    .filter(|r| !r.anomalies.is_empty())
+                    .map_or(String::new(), |r| {
+                        let reasons: Vec<String> =
+                            r.anomalies.iter().map(|a| a.to_string()).collect();
+                        format!(" — ⚠ {}", reasons.join("; "))
+                    });
+            let filter = app.filter_expression.as_ref().map_or(String::new(), |f| {
+                format!(
+                    " — filter: {f} ({} of {})",
+                    app.visible.len(),
+                    app.txs.len()
+                )
+            });
+            let search = app.search.as_ref().map_or(String::new(), |s| {
+                format!(" — /{s}: {} matches", search_matches(app))
+            });
+            format!("{filter}{search}{anomalies}{raw_name}{rule}{tags}{notes}")
+        }
+        View::Subscriptions => app
+            .subscriptions
+            .get(app.selected_in_view())
+            .filter(|s| !s.missed.is_empty())
+            .map_or(String::new(), |s| {
+                let dates: Vec<String> = s.missed.iter().map(|d| d.to_string()).collect();
+                format!(" — missed: {}", dates.join(", "))
+            }),
+        View::Errors if app.lenient => " — parsed leniently".to_string(),
+        View::Reports | View::Budgets | View::Forecast | View::Errors => String::new(),
+    }
+}
+
+/// Account IDs are shown with all but their last four characters hidden.
+fn mask_acctid(acctid: &str) -> String {
+    let chars: Vec<char> = acctid.chars().collect();
+    let hidden = chars.len().saturating_sub(4);
+    "•".repeat(hidden) + &chars[hidden..].iter().collect::<String>()
+}
+
+fn account_label(account: &AccountKey) -> String {
+    format!("{} {}", mask_acctid(&account.acctid), account.accttype)
+}
+
+fn category_label(app: &App, row: &TransactionRow) -> String {
+    if let Some(annotation) = app.annotation(row).filter(|a| !a.splits.is_empty()) {
+        let categories: Vec<&str> = annotation
+            .splits
+            .iter()
+            .map(|s| s.category.as_str())
+            .collect();
+        return format!("Split: {}", categories.join(", "));
+    }
+
+    match (&row.category, row.transfer) {
+        (Some(c), true) => format!("⇄ {}", c.category),
+        (Some(c), false) => c.category.clone(),
+        (None, true) => "⇄ Transfer".to_string(),
+        (None, false) => String::new(),
+    }
+}
+
+fn search_matches(app: &App) -> usize {
+    app.visible
+        .iter()
+        .filter(|&&i| app.matches_search(&app.txs[i]))
+        .count()
+}
+
+/// A cell with every occurrence of the search in `text` highlighted, ignoring case.
+fn highlighted(text: String, search: Option<&str>) -> Cell<'static> {
+    let lower = text.to_lowercase();
+    let Some(search) = search
+        .map(str::to_lowercase)
+        // Lowercasing changed the byte offsets, so they cannot be used on `text`
+        .filter(|s| !s.is_empty() && lower.len() == text.len())
+    else {
+        return Cell::from(text);
+    };
+
+    let mut spans = Vec::new();
+    let mut copied = 0;
+    for (start, found) in lower.match_indices(&search) {
+        let end = start + found.len();
+        if !text.is_char_boundary(start) || !text.is_char_boundary(end) {
+            return Cell::from(text);
+        }
+        spans.push(Span::raw(text[copied..start].to_string()));
+        spans.push(Span::styled(
+            text[start..end].to_string(),
+            Style::default().fg(Color::Black).bg(Color::Yellow),
+        ));
+        copied = end;
+    }
+    spans.push(Span::raw(text[copied..].to_string()));
+    Cell::from(Line::from(spans))
+}
+
+fn highlight_style() -> Style {
+    Style::default()
+        .bg(Color::DarkGray)
+        .add_modifier(Modifier::BOLD)
+}
+
+pub fn format_amount(a: f64) -> String {
+    if a < 0.0 {
+        format!("-${:.2}", -a)
+    } else {
+        // abs() turns -0.0 into 0.0
+        format!(" ${:.2}", a.abs())
+    }
+}